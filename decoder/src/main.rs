@@ -3,7 +3,19 @@ use std::{
     str::FromStr,
 };
 
-use libkeystore::{KeyStore, KeystoreError};
+use chrono::Utc;
+use libdgc::{
+    certlogic::{ExternalParameters, RuleSet},
+    cwt::{KidMatch, SignatureMode, SignatureReport},
+    dgc::{Decoded, DigitalGreenCertificate, OwnedCertificate, Verified},
+    hcert::HCertPayload,
+    policy::{Mode, Policy},
+    revocation::{Blocklist, BlocklistEntry, ChunkStore, Revocation, RevocationStore},
+    schema::SchemaValidator,
+    valueset::{RatDevices, ValueSets},
+    DecodeError,
+};
+use libkeystore::{KeyStore, KeystoreError, PublicKey};
 use log::LevelFilter;
 use reqwest::Url;
 
@@ -16,6 +28,14 @@ struct CommandLineInterface {
     #[clap(short, long, parse(from_occurrences))]
     verbose: usize,
 
+    #[clap(long, global = true)]
+    /// Path to a directory of EU DCC value sets (JSON), overriding the bundled ones.
+    valuesets: Option<PathBuf>,
+
+    #[clap(long, global = true)]
+    /// Path to the JRC list of rapid antigen tests (JSON export).
+    rat_devices: Option<PathBuf>,
+
     #[clap(subcommand)]
     commands: Commands,
 }
@@ -28,6 +48,52 @@ enum Commands {
         /// URL or path to a JSON file storing public keys.
         keystore: String,
 
+        #[clap(long)]
+        /// Also accept DER encoded signatures.
+        lenient: bool,
+
+        #[clap(short, long)]
+        /// Path to a directory of revocation batches (JSON).
+        revocation: Option<PathBuf>,
+
+        #[clap(long)]
+        /// Path to a directory of partitioned revocation chunks.
+        revocation_chunks: Option<PathBuf>,
+
+        #[clap(short, long)]
+        /// Path to a blocklist of hashed UVCIs (TousAntiCovid format).
+        blocklist: Option<PathBuf>,
+
+        #[clap(long)]
+        /// Path to a text file of blocklisted UVCIs, one per line.
+        uvci_blocklist: Option<PathBuf>,
+
+        #[clap(long)]
+        /// Path to a directory or file of business rules (DCCG JSON).
+        rules: Option<PathBuf>,
+
+        #[clap(long, requires = "rules")]
+        /// Arrival country whose rules apply, e.g. FR; defaults to the issuing one.
+        country: Option<String>,
+
+        #[clap(long)]
+        /// Acceptance policy checked over every certificate of the image: vaccinated, booster, pcr, rat, tested, recovered, 2g, 3g or 2g+.
+        policy: Option<Mode>,
+
+        /// Path to the image to scan for QR codes.
+        image: PathBuf,
+    },
+
+    /// Verifies a DGC cryptographic signature against a single public key
+    VerifyWithKey {
+        #[clap(short, long)]
+        /// Path to a PEM or DER certificate or public key.
+        key: PathBuf,
+
+        #[clap(long)]
+        /// Also accept DER encoded signatures.
+        lenient: bool,
+
         /// Path to the image to scan for QR codes.
         image: PathBuf,
     },
@@ -58,14 +124,65 @@ fn main() {
 
     let _ = setup_logger(log_level);
 
+    if args.valuesets.is_some() || args.rat_devices.is_some() {
+        load_value_sets(args.valuesets, args.rat_devices);
+    }
+
     match args.commands {
-        Commands::Verify { keystore, image } => {
+        Commands::Verify {
+            keystore,
+            lenient,
+            revocation,
+            revocation_chunks,
+            blocklist,
+            uvci_blocklist,
+            rules,
+            country,
+            policy,
+            image,
+        } => {
             let keystore = get_keystore(&keystore);
 
-            scan_image(image, Some(&keystore));
+            let revocation = revocation.map(|dir| {
+                RevocationStore::load_from_dir(dir)
+                    .map_err(|e| format!("Unable to load revocation batches: {:?}", e))
+                    .unwrap()
+            });
+
+            let revocation_chunks = revocation_chunks.map(|dir| {
+                ChunkStore::open(dir)
+                    .map_err(|e| format!("Unable to load revocation lists: {:?}", e))
+                    .unwrap()
+            });
+
+            let blocklist = load_blocklist(blocklist, uvci_blocklist);
+
+            let rules = rules.map(load_rules);
+
+            let checks = Checks {
+                revocation: revocation.as_ref(),
+                revocation_chunks: revocation_chunks.as_ref(),
+                blocklist: blocklist.as_ref(),
+                rules: rules.as_ref(),
+                country: country.as_deref(),
+                policy: policy.map(Policy::new),
+            };
+
+            scan_image(image, Some(&keystore), signature_mode(lenient), &checks);
+        }
+        Commands::VerifyWithKey {
+            key,
+            lenient,
+            image,
+        } => {
+            let key = PublicKey::from_file(key)
+                .map_err(|e| format!("Unable to load public key: {:?}", e))
+                .unwrap();
+
+            scan_image_with_key(image, &key, signature_mode(lenient));
         }
         Commands::Decode { image } => {
-            scan_image(image, None);
+            scan_image(image, None, SignatureMode::Strict, &Checks::default());
         }
 
         Commands::ListKeystore { keystore } => {
@@ -76,6 +193,78 @@ fn main() {
     }
 }
 
+fn load_value_sets(dir: Option<PathBuf>, rat_devices: Option<PathBuf>) {
+    let mut value_sets = ValueSets::bundled();
+
+    if let Some(dir) = dir {
+        value_sets
+            .load_dir(dir)
+            .map_err(|e| format!("Unable to load value sets: {:?}", e))
+            .unwrap();
+    }
+
+    if let Some(path) = rat_devices {
+        let mut devices = RatDevices::new();
+
+        devices
+            .load_file(path)
+            .map_err(|e| format!("Unable to load rapid antigen test devices: {:?}", e))
+            .unwrap();
+
+        value_sets.set_rat_devices(devices);
+    }
+
+    value_sets.install();
+}
+
+fn signature_mode(lenient: bool) -> SignatureMode {
+    if lenient {
+        SignatureMode::Lenient
+    } else {
+        SignatureMode::Strict
+    }
+}
+
+fn load_blocklist(hashed: Option<PathBuf>, plain: Option<PathBuf>) -> Option<Blocklist> {
+    if hashed.is_none() && plain.is_none() {
+        return None;
+    }
+
+    let mut blocklist = Blocklist::new();
+
+    if let Some(path) = hashed {
+        blocklist
+            .load_hashed_file(path)
+            .map_err(|e| format!("Unable to load blocklist: {:?}", e))
+            .unwrap();
+    }
+
+    if let Some(path) = plain {
+        blocklist
+            .load_plain_file(path)
+            .map_err(|e| format!("Unable to load UVCI blocklist: {:?}", e))
+            .unwrap();
+    }
+
+    Some(blocklist)
+}
+
+fn load_rules(path: PathBuf) -> RuleSet {
+    let mut rules = RuleSet::new();
+
+    let result = if path.is_dir() {
+        rules.load_dir(path)
+    } else {
+        rules.load_file(path)
+    };
+
+    result
+        .map_err(|e| format!("Unable to load business rules: {:?}", e))
+        .unwrap();
+
+    rules
+}
+
 fn get_keystore(txt: &str) -> KeyStore {
     keystore_from(txt)
         .map_err(|e| {
@@ -103,48 +292,99 @@ fn keystore_from(txt: &str) -> Result<KeyStore, KeystoreError> {
 fn list_keystore(keystore: &KeyStore) {
     for (id, key) in keystore.pubkeys() {
         println!("Key id '{}':", id);
-        println!("\tIssuer: {}", key.issuer());
-        println!("\tSubject: {}", key.subject());
 
-        let validity = key.validity();
+        if let Some(computed) = keystore.computed_kid(id).filter(|computed| *computed != id) {
+            println!(
+                "\tWARNING: key id does not match certificate (computed: '{}')",
+                computed
+            );
+        }
+
+        println!("\tIssuer: {}", key.issuer);
+        println!("\tSubject: {}", key.subject);
 
         println!(
             "\tValidity: from {} to {}",
-            validity.not_before.to_rfc2822(),
-            validity.not_after.to_rfc2822()
+            key.not_before.to_rfc2822(),
+            key.not_after.to_rfc2822()
+        );
+    }
+
+    let mismatches = keystore.mismatched_kids().count();
+
+    if mismatches > 0 {
+        println!();
+        println!(
+            "{} key(s) with a key id that does not match their certificate.",
+            mismatches
         );
     }
 }
 
-fn scan_image<P: AsRef<Path>>(image: P, keystore: Option<&KeyStore>) {
+fn scan_image<P: AsRef<Path>>(
+    image: P,
+    keystore: Option<&KeyStore>,
+    mode: SignatureMode,
+    checks: &Checks,
+) {
     log::info!(
         "Searching certificates in image: {}",
         image.as_ref().to_string_lossy()
     );
 
+    let validator = SchemaValidator::new();
+
     match libdgc::decode_image(image) {
         Ok(scanned) => {
             log::info!(target:"decoder", "Found {} valid QR codes.", scanned.len());
 
+            let mut verified = vec![];
+
             for (i, raw_cert) in scanned.into_iter().enumerate() {
                 println!("Certificate {}:", i);
 
                 match (raw_cert.decode(), keystore) {
-                    (Ok(decoded), Some(keystore)) => match decoded.verify_signature(keystore) {
-                        Ok((kid, verified_dgc)) => {
-                            let pubkey = keystore.pubkey_as_cert(&kid).unwrap();
+                    (Ok(decoded), Some(keystore)) => match verify(&decoded, keystore, mode, checks)
+                    {
+                        Ok((report, verified_dgc)) => {
+                            let kid = report.kid;
+                            let pubkey = keystore.key_info(&kid).unwrap();
 
                             println!("Signature is verified successfully with key id '{}'", kid);
-                            println!("Subject: {}", pubkey.subject());
-                            println!("Issuer: {}", pubkey.issuer());
 
-                            let begin = pubkey.validity().not_before.to_rfc2822();
-                            let end = pubkey.validity().not_after.to_rfc2822();
+                            if report.der_encoded_signature {
+                                println!("WARNING: issuer emitted a DER encoded signature.");
+                            }
+
+                            println!("Subject: {}", pubkey.subject);
+                            println!("Issuer: {}", pubkey.issuer);
+
+                            let begin = pubkey.not_before.to_rfc2822();
+                            let end = pubkey.not_after.to_rfc2822();
 
                             println!("Valid from {} to {}.", begin, end);
                             println!();
 
+                            print_checks(checks);
+
+                            print_violations(&decoded, &validator);
+                            print_lints(verified_dgc.hcert_payload());
+                            print_rules(verified_dgc.hcert_payload(), &kid, checks);
+
                             println!("{}", verified_dgc);
+
+                            verified.push(verified_dgc.into_owned());
+                        }
+                        Err(DecodeError::Blocklisted(entry)) => {
+                            print_blocklisted(entry);
+                            println!();
+                        }
+                        Err(DecodeError::Revoked(revocation)) => {
+                            print_revoked(revocation);
+                            println!();
+                        }
+                        Err(DecodeError::RevocationCheckFailed(e)) => {
+                            log::error!("Unable to check revocation: {:?}", e)
                         }
                         Err(_e) => {
                             log::error!("Bad signature !")
@@ -152,8 +392,12 @@ fn scan_image<P: AsRef<Path>>(image: P, keystore: Option<&KeyStore>) {
                     },
 
                     (Ok(decoded), None) => {
+                        print_violations(&decoded, &validator);
+
                         let hcert = decoded.decode_payload().unwrap();
 
+                        print_lints(&hcert);
+
                         println!("{}", hcert);
                     }
                     (Err(e), _) => {
@@ -161,6 +405,251 @@ fn scan_image<P: AsRef<Path>>(image: P, keystore: Option<&KeyStore>) {
                     }
                 }
             }
+
+            print_policy(&verified, checks);
+        }
+
+        Err(e) => {
+            log::error!("Failed to use image: {}", e);
+        }
+    }
+}
+
+/// Additional checks run on certificates whose signature is valid.
+#[derive(Default)]
+struct Checks<'a> {
+    revocation: Option<&'a RevocationStore>,
+    revocation_chunks: Option<&'a ChunkStore>,
+    blocklist: Option<&'a Blocklist>,
+    rules: Option<&'a RuleSet>,
+    country: Option<&'a str>,
+    policy: Option<Policy>,
+}
+
+/// Verifies the signature, and rejects blocklisted or revoked certificates
+/// when a blocklist or revocation lists are given.
+fn verify<'buf>(
+    decoded: &'buf DigitalGreenCertificate<Decoded<'buf>>,
+    keystore: &KeyStore,
+    mode: SignatureMode,
+    checks: &Checks,
+) -> Result<(SignatureReport, DigitalGreenCertificate<Verified<'buf>>), DecodeError<'buf>> {
+    let (report, verified) = match checks.blocklist {
+        Some(blocklist) => decoded.verify_signature_with_blocklist(keystore, mode, blocklist),
+        None => decoded.verify_signature_with_mode(keystore, mode),
+    }?;
+
+    if let Some(store) = checks.revocation {
+        decoded.reject_revoked(store, Utc::now())?;
+    }
+
+    if let Some(store) = checks.revocation_chunks {
+        decoded.reject_revoked_chunks(store)?;
+    }
+
+    Ok((report, verified))
+}
+
+fn print_blocklisted(entry: BlocklistEntry) {
+    match entry {
+        BlocklistEntry::Hashed => {
+            println!("REJECTED: certificate identifier hash is blocklisted.")
+        }
+        BlocklistEntry::Plain => println!("REJECTED: certificate identifier is blocklisted."),
+    }
+}
+
+fn print_revoked(revocation: Revocation) {
+    match revocation {
+        Revocation::Batch {
+            batch_id,
+            hash_type,
+        } => println!(
+            "REVOKED: found in batch '{}' ({:?} hash).",
+            batch_id, hash_type
+        ),
+        Revocation::Chunk(hash_type) => println!(
+            "REVOKED: found in revocation chunks ({:?} hash).",
+            hash_type
+        ),
+        Revocation::ProbableChunk {
+            hash_type,
+            false_positive_rate,
+        } => println!(
+            "REVOKED: matched a Bloom filter ({:?} hash, false positive rate: {}).",
+            hash_type, false_positive_rate
+        ),
+    }
+}
+
+/// Reports the checks a verified certificate passed.
+fn print_checks(checks: &Checks) {
+    if checks.blocklist.is_some() {
+        println!("Certificate is not blocklisted.");
+    }
+
+    if checks.revocation.is_some() {
+        println!("Certificate is not revoked.");
+    }
+
+    if checks.revocation_chunks.is_some() {
+        println!("Certificate is not in revocation chunks.");
+    }
+
+    if checks.blocklist.is_some()
+        || checks.revocation.is_some()
+        || checks.revocation_chunks.is_some()
+    {
+        println!();
+    }
+}
+
+fn print_violations(decoded: &DigitalGreenCertificate<Decoded>, validator: &SchemaValidator) {
+    match decoded.validate_schema(validator) {
+        Ok(violations) if violations.is_empty() => {}
+        Ok(violations) => {
+            for violation in violations {
+                println!("WARNING: schema violation at {}", violation);
+            }
+            println!();
+        }
+        Err(e) => log::error!("Unable to validate certificate: {:?}", e),
+    }
+}
+
+fn print_lints(payload: &HCertPayload) {
+    let lints: Vec<_> = payload
+        .certificate()
+        .map(|cert| cert.lint())
+        .unwrap_or_default();
+
+    for lint in &lints {
+        println!("WARNING: {}", lint);
+    }
+
+    if !lints.is_empty() {
+        println!();
+    }
+}
+
+fn print_policy(verified: &[OwnedCertificate], checks: &Checks) {
+    let policy = match checks.policy {
+        Some(policy) => policy,
+        None => return,
+    };
+
+    let certificates: Vec<_> = verified
+        .iter()
+        .filter_map(|dgc| dgc.hcert_payload().certificate())
+        .collect();
+
+    let assessment = policy.check(&certificates, Utc::now());
+
+    for rejection in &assessment.rejections {
+        println!("Not counted for the policy: {}", rejection);
+    }
+
+    if assessment.accepted {
+        println!(
+            "ACCEPTED by {:?} policy ({:?}).",
+            policy.mode, assessment.statuses
+        );
+    } else {
+        println!(
+            "REJECTED by {:?} policy ({:?}).",
+            policy.mode, assessment.statuses
+        );
+    }
+}
+
+fn print_rules(payload: &HCertPayload, kid: &str, checks: &Checks) {
+    let rules = match checks.rules {
+        Some(rules) => rules,
+        None => return,
+    };
+
+    let country = checks.country.unwrap_or(&payload.iss);
+
+    let external = ExternalParameters::now(payload, Some(kid), country, &ValueSets::global());
+
+    match rules.validate(payload, &external) {
+        Ok(results) if results.is_empty() => {
+            println!(
+                "No business rules of {} apply to this certificate.",
+                country
+            )
+        }
+        Ok(results) => {
+            let failed = results.iter().filter(|result| !result.passed()).count();
+
+            println!("Business rules of {}:", country);
+
+            for result in &results {
+                println!("\t{}", result);
+            }
+
+            if failed > 0 {
+                println!(
+                    "REJECTED: {} of {} rules not passed.",
+                    failed,
+                    results.len()
+                );
+            }
+        }
+        Err(e) => log::error!("Unable to check business rules: {:?}", e),
+    }
+    println!();
+}
+
+fn scan_image_with_key<P: AsRef<Path>>(image: P, key: &PublicKey, mode: SignatureMode) {
+    log::info!(
+        "Searching certificates in image: {}",
+        image.as_ref().to_string_lossy()
+    );
+
+    match libdgc::decode_image(image) {
+        Ok(scanned) => {
+            log::info!(target:"decoder", "Found {} valid QR codes.", scanned.len());
+
+            for (i, raw_cert) in scanned.into_iter().enumerate() {
+                println!("Certificate {}:", i);
+
+                match raw_cert.decode() {
+                    Ok(decoded) => match decoded.verify_signature_with_key(key, mode) {
+                        Ok((report, verified_dgc)) => {
+                            println!("Signature is verified successfully with provided key");
+
+                            if report.der_encoded_signature {
+                                println!("WARNING: issuer emitted a DER encoded signature.");
+                            }
+
+                            let kid = report.kid.as_deref().unwrap_or("<none>");
+
+                            match report.kid_match {
+                                KidMatch::Matched => {
+                                    println!("Key id '{}' matches provided key.", kid)
+                                }
+                                KidMatch::Mismatched { expected } => println!(
+                                    "Key id '{}' does NOT match provided key ('{}').",
+                                    kid, expected
+                                ),
+                                KidMatch::Unknown => {
+                                    println!("Key id '{}' could not be checked.", kid)
+                                }
+                            }
+                            println!();
+
+                            println!("{}", verified_dgc);
+                        }
+                        Err(_e) => {
+                            log::error!("Bad signature !")
+                        }
+                    },
+                    Err(e) => {
+                        println!("Failed to decode QR code: {:?}", e);
+                    }
+                }
+            }
         }
 
         Err(e) => {
@@ -174,6 +663,7 @@ fn setup_logger(level: LevelFilter) -> Result<(), fern::InitError> {
         .level(level)
         .level_for("keystore", level)
         .level_for("dgc", level)
+        .level_for("revocation", level)
         .format(|out, message, record| {
             out.finish(format_args!(
                 "[{}][{}] {}",
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use serde_json::{json, Value as Json};
+
+use crate::{hcert::HCertPayload, valueset::ValueSets};
+
+use super::CertLogicError;
+
+/// Parameters of the validation, found under `external` in the data rules
+/// are evaluated over.
+#[derive(Debug, Clone)]
+pub struct ExternalParameters {
+    pub validation_clock: DateTime<FixedOffset>,
+
+    /// Codes of every value set, by id
+    pub value_sets: HashMap<String, Vec<String>>,
+
+    /// Country where the certificate is checked
+    pub country_code: String,
+
+    pub region: Option<String>,
+
+    /// Expiration and issuing dates of the CWT
+    pub exp: DateTime<FixedOffset>,
+    pub iat: DateTime<FixedOffset>,
+
+    /// Country of the issuer of the CWT
+    pub issuer_country_code: String,
+
+    /// Key id found in the COSE header, base64 encoded
+    pub kid: Option<String>,
+}
+
+impl ExternalParameters {
+    /// Parameters to validate `payload`, signed with the key `kid`, in
+    /// `country_code` at `validation_clock`, with the codes of `value_sets`.
+    pub fn new(
+        payload: &HCertPayload,
+        kid: Option<&str>,
+        validation_clock: DateTime<FixedOffset>,
+        country_code: &str,
+        value_sets: &ValueSets,
+    ) -> Self {
+        let value_sets = value_sets
+            .sets()
+            .map(|set| (set.id.clone(), set.values.keys().cloned().collect()))
+            .collect();
+
+        ExternalParameters {
+            validation_clock,
+            value_sets,
+            country_code: country_code.to_owned(),
+            region: None,
+            exp: payload.expiring_at().into(),
+            iat: payload.issued_at().into(),
+            issuer_country_code: payload.iss.to_string(),
+            kid: kid.map(str::to_owned),
+        }
+    }
+
+    /// Parameters to validate `payload`, signed with the key `kid`, in
+    /// `country_code` right now.
+    pub fn now(
+        payload: &HCertPayload,
+        kid: Option<&str>,
+        country_code: &str,
+        value_sets: &ValueSets,
+    ) -> Self {
+        Self::new(payload, kid, Utc::now().into(), country_code, value_sets)
+    }
+
+    pub fn to_json(&self) -> Json {
+        let date_time = |dt: &DateTime<FixedOffset>| dt.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        json!({
+            "validationClock": date_time(&self.validation_clock),
+            "valueSets": self.value_sets,
+            "countryCode": self.country_code,
+            "region": self.region,
+            "exp": date_time(&self.exp),
+            "iat": date_time(&self.iat),
+            "issuerCountryCode": self.issuer_country_code,
+            "kid": self.kid,
+        })
+    }
+}
+
+/// Data business rules are evaluated over: the certificate in its JSON
+/// form under `payload`, and the parameters under `external`.
+pub fn data(payload: &HCertPayload, external: &ExternalParameters) -> Result<Json, CertLogicError> {
+    let certificate = serde_json::to_value(payload.certificate())
+        .map_err(CertLogicError::DataSerializationFailed)?;
+
+    Ok(json!({
+        "payload": certificate,
+        "external": external.to_json(),
+    }))
+}
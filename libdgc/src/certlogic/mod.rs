@@ -0,0 +1,490 @@
+//! Evaluator for [CertLogic](https://github.com/ehn-dcc-development/dgc-business-rules/tree/main/certlogic),
+//! the JsonLogic dialect in which the EU distributes its business rules.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value as Json;
+
+mod external;
+mod rules;
+mod value;
+
+pub use external::{data, ExternalParameters};
+pub use rules::{
+    Rule, RuleCertificateType, RuleDescription, RuleError, RuleOutcome, RuleResult, RuleSet,
+    RuleType,
+};
+pub use value::Value;
+
+static DATE_TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})(?:T(\d{2}):(\d{2}):(\d{2})(?:\.(\d+))?(Z|[+-]\d{2}(?::?\d{2})?)?)?$",
+    )
+    .unwrap()
+});
+
+static DATE_OF_BIRTH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d{4})(?:-(\d{2})(?:-(\d{2}))?)?$").unwrap());
+
+#[derive(Debug)]
+pub enum CertLogicError {
+    /// Neither a literal, an array nor an operation, e.g. a float
+    InvalidExpression(Json),
+    UnknownOperation(String),
+    /// Wrong number or kind of operands
+    InvalidOperands(&'static str),
+    TypeMismatch {
+        operation: &'static str,
+        found: &'static str,
+    },
+    /// The value used as a condition is neither truthy nor falsy
+    NotBoolean(&'static str),
+    InvalidDateTime(String),
+    /// Rules written for another engine than CertLogic
+    UnsupportedEngine(String),
+    DataSerializationFailed(serde_json::Error),
+}
+
+/// Evaluates the CertLogic expression `expr` over `data`, usually built with
+/// [`data`].
+pub fn evaluate(expr: &Json, data: &Json) -> Result<Value, CertLogicError> {
+    eval(expr, &Value::from(data))
+}
+
+fn eval(expr: &Json, data: &Value) -> Result<Value, CertLogicError> {
+    use CertLogicError::*;
+
+    let (operation, operands) = match expr {
+        Json::Number(n) => {
+            return n
+                .as_i64()
+                .map(Value::Integer)
+                .ok_or_else(|| InvalidExpression(expr.clone()))
+        }
+        Json::Array(items) => {
+            return items
+                .iter()
+                .map(|item| eval(item, data))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+        Json::Object(fields) if fields.len() == 1 => fields.iter().next().unwrap(),
+        Json::Object(_) => return Err(InvalidExpression(expr.clone())),
+        _ => return Ok(Value::from(expr)),
+    };
+
+    if operation == "var" {
+        return var(operands, data);
+    }
+
+    let operands = match operands {
+        Json::Array(operands) => operands.as_slice(),
+        _ => return Err(InvalidExpression(expr.clone())),
+    };
+
+    match operation.as_str() {
+        "if" => {
+            let [guard, then, otherwise] = exactly::<3>("if", operands)?;
+
+            let guard = eval(guard, data)?;
+
+            if guard.is_truthy() {
+                eval(then, data)
+            } else if guard.is_falsy() {
+                eval(otherwise, data)
+            } else {
+                Err(NotBoolean("if"))
+            }
+        }
+
+        "===" => {
+            let [left, right] = exactly::<2>("===", operands)?;
+
+            Ok(Value::Bool(eval(left, data)? == eval(right, data)?))
+        }
+
+        "and" => {
+            if operands.len() < 2 {
+                return Err(InvalidOperands("and"));
+            }
+
+            let mut last = Value::Null;
+
+            for operand in operands {
+                last = eval(operand, data)?;
+
+                if last.is_falsy() {
+                    return Ok(last);
+                } else if !last.is_truthy() {
+                    return Err(NotBoolean("and"));
+                }
+            }
+
+            Ok(last)
+        }
+
+        "!" => {
+            let [operand] = exactly::<1>("!", operands)?;
+
+            let operand = eval(operand, data)?;
+
+            if operand.is_falsy() {
+                Ok(Value::Bool(true))
+            } else if operand.is_truthy() {
+                Ok(Value::Bool(false))
+            } else {
+                Err(NotBoolean("!"))
+            }
+        }
+
+        "<" | ">" | "<=" | ">=" => {
+            let operation = comparison(operation);
+
+            compare(operation, operands, data, |value| match value {
+                Value::Integer(i) => Some(*i),
+                _ => None,
+            })
+        }
+
+        "before" | "after" | "not-before" | "not-after" => {
+            let operation = comparison(operation);
+
+            compare(operation, operands, data, |value| match value {
+                Value::DateTime(dt) => Some(dt.timestamp_millis()),
+                _ => None,
+            })
+        }
+
+        "in" => {
+            let [needle, haystack] = exactly::<2>("in", operands)?;
+
+            let needle = eval(needle, data)?;
+
+            match eval(haystack, data)? {
+                Value::Array(items) => Ok(Value::Bool(items.contains(&needle))),
+                other => Err(TypeMismatch {
+                    operation: "in",
+                    found: other.type_name(),
+                }),
+            }
+        }
+
+        "+" => {
+            let [left, right] = exactly::<2>("+", operands)?;
+
+            match (eval(left, data)?, eval(right, data)?) {
+                (Value::Integer(left), Value::Integer(right)) => left
+                    .checked_add(right)
+                    .map(Value::Integer)
+                    .ok_or(InvalidOperands("+")),
+                (Value::Integer(_), other) | (other, _) => Err(TypeMismatch {
+                    operation: "+",
+                    found: other.type_name(),
+                }),
+            }
+        }
+
+        "plusTime" => {
+            let [date_time, amount, unit] = exactly::<3>("plusTime", operands)?;
+
+            let amount = amount.as_i64().ok_or(InvalidOperands("plusTime"))?;
+            let unit = unit.as_str().ok_or(InvalidOperands("plusTime"))?;
+
+            let date_time = match eval(date_time, data)? {
+                Value::String(s) => parse_date_time(&s)?,
+                Value::DateTime(dt) => dt,
+                other => {
+                    return Err(TypeMismatch {
+                        operation: "plusTime",
+                        found: other.type_name(),
+                    })
+                }
+            };
+
+            plus_time(date_time, amount, unit).map(Value::DateTime)
+        }
+
+        "dccDateOfBirth" => {
+            let [dob] = exactly::<1>("dccDateOfBirth", operands)?;
+
+            match eval(dob, data)? {
+                Value::String(s) => parse_date_of_birth(&s).map(Value::DateTime),
+                other => Err(TypeMismatch {
+                    operation: "dccDateOfBirth",
+                    found: other.type_name(),
+                }),
+            }
+        }
+
+        "reduce" => {
+            let [operand, lambda, initial] = exactly::<3>("reduce", operands)?;
+
+            let initial = eval(initial, data)?;
+
+            match eval(operand, data)? {
+                Value::Null => Ok(initial),
+                Value::Array(items) => {
+                    items.into_iter().try_fold(initial, |accumulator, current| {
+                        let mut scope = BTreeMap::new();
+                        scope.insert("accumulator".to_owned(), accumulator);
+                        scope.insert("current".to_owned(), current);
+
+                        eval(lambda, &Value::Object(scope))
+                    })
+                }
+                other => Err(TypeMismatch {
+                    operation: "reduce",
+                    found: other.type_name(),
+                }),
+            }
+        }
+
+        "extractFromUVCI" => {
+            let [uvci, index] = exactly::<2>("extractFromUVCI", operands)?;
+
+            let index = index.as_u64().ok_or(InvalidOperands("extractFromUVCI"))?;
+
+            match eval(uvci, data)? {
+                Value::Null => Ok(Value::Null),
+                Value::String(uvci) => Ok(extract_from_uvci(&uvci, index as usize)
+                    .map(|fragment| Value::String(fragment.to_owned()))
+                    .unwrap_or(Value::Null)),
+                other => Err(TypeMismatch {
+                    operation: "extractFromUVCI",
+                    found: other.type_name(),
+                }),
+            }
+        }
+
+        _ => Err(UnknownOperation(operation.clone())),
+    }
+}
+
+/// Operands as an array of `N`, whose length is checked.
+fn exactly<'o, const N: usize>(
+    operation: &'static str,
+    operands: &'o [Json],
+) -> Result<&'o [Json; N], CertLogicError> {
+    operands
+        .try_into()
+        .map_err(|_| CertLogicError::InvalidOperands(operation))
+}
+
+/// Looks up a dot separated path, numbers indexing arrays. Anything missing
+/// along the way evaluates to null.
+fn var(path: &Json, data: &Value) -> Result<Value, CertLogicError> {
+    let path = match path {
+        Json::String(path) => path.clone(),
+        Json::Number(n) if n.is_u64() => n.to_string(),
+        _ => return Err(CertLogicError::InvalidOperands("var")),
+    };
+
+    if path.is_empty() {
+        return Ok(data.clone());
+    }
+
+    let mut current = data;
+
+    for fragment in path.split('.') {
+        let next = match current {
+            Value::Array(items) => fragment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            Value::Object(fields) => fields.get(fragment),
+            _ => None,
+        };
+
+        match next {
+            Some(next) => current = next,
+            None => return Ok(Value::Null),
+        }
+    }
+
+    Ok(current.clone())
+}
+
+fn comparison(operation: &str) -> &'static str {
+    match operation {
+        "<" | "before" => "<",
+        ">" | "after" => ">",
+        "<=" | "not-after" => "<=",
+        _ => ">=",
+    }
+}
+
+/// Chained comparison of 2 or 3 operands, `a < b < c` checking that `b` is
+/// between `a` and `c`.
+fn compare<F>(
+    operation: &'static str,
+    operands: &[Json],
+    data: &Value,
+    key: F,
+) -> Result<Value, CertLogicError>
+where
+    F: Fn(&Value) -> Option<i64>,
+{
+    if !(2..=3).contains(&operands.len()) {
+        return Err(CertLogicError::InvalidOperands(operation));
+    }
+
+    let mut keys = vec![];
+
+    for operand in operands {
+        let value = eval(operand, data)?;
+
+        keys.push(key(&value).ok_or(CertLogicError::TypeMismatch {
+            operation,
+            found: value.type_name(),
+        })?);
+    }
+
+    let holds = keys.windows(2).all(|pair| match operation {
+        "<" => pair[0] < pair[1],
+        ">" => pair[0] > pair[1],
+        "<=" => pair[0] <= pair[1],
+        _ => pair[0] >= pair[1],
+    });
+
+    Ok(Value::Bool(holds))
+}
+
+/// Parses dates and date-times. A date is midnight UTC, and a date-time
+/// without offset is UTC.
+pub fn parse_date_time(s: &str) -> Result<DateTime<FixedOffset>, CertLogicError> {
+    parse(&DATE_TIME, s)
+}
+
+/// Parses dates of birth, partial ones being completed with the last day of
+/// the year or month. Unlike other dates, they cannot have a time.
+pub fn parse_date_of_birth(s: &str) -> Result<DateTime<FixedOffset>, CertLogicError> {
+    parse(&DATE_OF_BIRTH, s)
+}
+
+fn parse(pattern: &Regex, s: &str) -> Result<DateTime<FixedOffset>, CertLogicError> {
+    let invalid = || CertLogicError::InvalidDateTime(s.to_owned());
+
+    let captures = pattern.captures(s).ok_or_else(invalid)?;
+
+    let number = |i: usize| {
+        captures
+            .get(i)
+            .map(|m| m.as_str().parse::<u32>().unwrap_or(0))
+    };
+
+    let year = number(1).unwrap_or(0) as i32;
+
+    let date = match (number(2), number(3)) {
+        (Some(month), Some(day)) => NaiveDate::from_ymd_opt(year, month, day),
+        (Some(month), None) => last_day_of_month(year, month),
+        _ => NaiveDate::from_ymd_opt(year, 12, 31),
+    }
+    .ok_or_else(invalid)?;
+
+    let nanos = captures.get(7).map_or(0, |fraction| {
+        let digits: String = fraction
+            .as_str()
+            .chars()
+            .chain("000000000".chars())
+            .take(9)
+            .collect();
+
+        digits.parse().unwrap_or(0)
+    });
+
+    let time = date
+        .and_hms_nano_opt(
+            number(4).unwrap_or(0),
+            number(5).unwrap_or(0),
+            number(6).unwrap_or(0),
+            nanos,
+        )
+        .ok_or_else(invalid)?;
+
+    let offset = match captures.get(8).map(|m| m.as_str()) {
+        None | Some("Z") => 0,
+        Some(offset) => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let digits = offset[1..].replace(':', "");
+
+            let hours: i32 = digits[..2].parse().map_err(|_| invalid())?;
+            let minutes: i32 = digits
+                .get(2..)
+                .filter(|m| !m.is_empty())
+                .map_or(Ok(0), str::parse)
+                .map_err(|_| invalid())?;
+
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    let offset = FixedOffset::east_opt(offset).ok_or_else(invalid)?;
+
+    offset
+        .from_local_datetime(&time)
+        .single()
+        .ok_or_else(invalid)
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)?
+        .pred_opt()
+        .filter(|_| month >= 1)
+}
+
+/// Adds `amount` units of time, months and years overflowing into the next
+/// month like JavaScript dates do, e.g. January 31st plus a month being
+/// March 3rd.
+fn plus_time(
+    date_time: DateTime<FixedOffset>,
+    amount: i64,
+    unit: &str,
+) -> Result<DateTime<FixedOffset>, CertLogicError> {
+    let overflow = || CertLogicError::InvalidOperands("plusTime");
+
+    let utc = date_time.naive_utc();
+
+    let result = match unit {
+        "hour" => utc.checked_add_signed(Duration::hours(amount)),
+        "day" => utc.checked_add_signed(Duration::days(amount)),
+        "month" => plus_months(utc, amount),
+        "year" => amount
+            .checked_mul(12)
+            .and_then(|months| plus_months(utc, months)),
+        _ => return Err(CertLogicError::InvalidOperands("plusTime")),
+    }
+    .ok_or_else(overflow)?;
+
+    Ok(Utc
+        .from_utc_datetime(&result)
+        .with_timezone(date_time.offset()))
+}
+
+fn plus_months(date_time: NaiveDateTime, months: i64) -> Option<NaiveDateTime> {
+    let date = date_time.date();
+
+    let total = i64::from(date.year()) * 12 + i64::from(date.month0()) + months;
+
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = total.rem_euclid(12) as u32 + 1;
+
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+
+    let shifted = first.checked_add_signed(Duration::days(i64::from(date.day0())))?;
+
+    Some(shifted.and_time(date_time.time()))
+}
+
+/// Fragment of a UVCI, split on `/`, `#` and `:` once its optional
+/// `URN:UVCI:` prefix is removed.
+fn extract_from_uvci(uvci: &str, index: usize) -> Option<&str> {
+    let uvci = uvci.strip_prefix("URN:UVCI:").unwrap_or(uvci);
+
+    uvci.split(['/', '#', ':']).nth(index)
+}
@@ -0,0 +1,576 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use chrono::{DateTime, FixedOffset};
+use serde::Deserialize;
+use serde_json::Value as Json;
+
+use crate::hcert::{CertificateKind, HCertPayload};
+
+use super::{data, evaluate, CertLogicError, ExternalParameters, Value};
+
+const ENGINE: &str = "CERTLOGIC";
+
+#[derive(Debug)]
+pub enum RuleError {
+    FileError(std::io::Error),
+    ParsingError(serde_json::Error),
+    /// Version of a rule which is not made of numbers, e.g. `1.0.x`
+    InvalidVersion {
+        identifier: String,
+        version: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum RuleType {
+    /// Set by the arrival country
+    Acceptance,
+    /// Set by the issuing country
+    Invalidation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum RuleCertificateType {
+    General,
+    Vaccination,
+    Test,
+    Recovery,
+}
+
+impl RuleCertificateType {
+    /// Type of rules, besides general ones, checking this kind of
+    /// certificate. Exemptions and national entries only have general ones.
+    pub fn of(kind: &CertificateKind) -> Self {
+        match kind {
+            CertificateKind::Vaccination(_) => RuleCertificateType::Vaccination,
+            CertificateKind::Test(_) => RuleCertificateType::Test,
+            CertificateKind::Recovery(_) => RuleCertificateType::Recovery,
+            _ => RuleCertificateType::General,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RuleDescription {
+    pub lang: String,
+    pub desc: String,
+}
+
+/// A business rule, as distributed by the EU DCC gateway.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Rule {
+    /// e.g. `VR-FR-0001`
+    pub identifier: String,
+
+    #[serde(rename = "Type")]
+    pub rule_type: RuleType,
+
+    pub country: String,
+
+    #[serde(default)]
+    pub region: Option<String>,
+
+    pub version: String,
+
+    #[serde(default)]
+    pub schema_version: String,
+
+    pub engine: String,
+
+    #[serde(default)]
+    pub engine_version: String,
+
+    pub certificate_type: RuleCertificateType,
+
+    #[serde(default)]
+    pub description: Vec<RuleDescription>,
+
+    pub valid_from: DateTime<FixedOffset>,
+
+    pub valid_to: DateTime<FixedOffset>,
+
+    /// Fields of the certificate the rule looks at, e.g. `v.0.dn`
+    #[serde(default)]
+    pub affected_fields: Vec<String>,
+
+    /// CertLogic expression, true when the certificate passes the rule
+    pub logic: Json,
+}
+
+impl Rule {
+    /// Description in `lang`, falling back to English then to the first one.
+    pub fn description(&self, lang: &str) -> Option<&str> {
+        self.description
+            .iter()
+            .find(|description| description.lang == lang)
+            .or_else(|| self.description.iter().find(|d| d.lang == "en"))
+            .or_else(|| self.description.first())
+            .map(|description| description.desc.as_str())
+    }
+
+    pub fn is_valid_at(&self, at: DateTime<FixedOffset>) -> bool {
+        self.valid_from <= at && at < self.valid_to
+    }
+
+    /// Evaluates the rule over `data`, built with [`data`].
+    pub fn evaluate(&self, data: &Json) -> RuleOutcome {
+        if !self.engine.eq_ignore_ascii_case(ENGINE) {
+            return RuleOutcome::Open(CertLogicError::UnsupportedEngine(self.engine.clone()));
+        }
+
+        match evaluate(&self.logic, data) {
+            Ok(Value::Bool(true)) => RuleOutcome::Passed,
+            Ok(Value::Bool(false)) => RuleOutcome::Failed,
+            Ok(_) => RuleOutcome::Open(CertLogicError::NotBoolean("rule")),
+            Err(e) => RuleOutcome::Open(e),
+        }
+    }
+
+    /// Version numbers, compared numerically, if the version is made of
+    /// dot separated numbers.
+    fn version_key(&self) -> Option<Vec<u32>> {
+        self.version
+            .split('.')
+            .map(|part| {
+                // `parse` alone would accept a sign.
+                if part.bytes().all(|b| b.is_ascii_digit()) {
+                    part.parse().ok()
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub enum RuleOutcome {
+    Passed,
+    Failed,
+    /// The rule could not be evaluated
+    Open(CertLogicError),
+}
+
+#[derive(Debug)]
+pub struct RuleResult<'r> {
+    pub rule: &'r Rule,
+    pub outcome: RuleOutcome,
+}
+
+impl RuleResult<'_> {
+    pub fn passed(&self) -> bool {
+        matches!(self.outcome, RuleOutcome::Passed)
+    }
+}
+
+/// Outcome, identifier and English description of the rule.
+impl fmt::Display for RuleResult<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = match &self.outcome {
+            RuleOutcome::Passed => "PASSED",
+            RuleOutcome::Failed => "FAILED",
+            RuleOutcome::Open(_) => "OPEN",
+        };
+
+        write!(f, "{} {}", outcome, self.rule.identifier)?;
+
+        if let Some(description) = self.rule.description("en") {
+            write!(f, ": {}", description)?;
+        }
+
+        match &self.outcome {
+            RuleOutcome::Open(e) => write!(f, " ({:?})", e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Business rules of every country, with every version of each, as a newer
+/// version may only become valid later.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: HashMap<String, Vec<Rule>>,
+}
+
+impl RuleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads every JSON file found in `dir`.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<(), RuleError> {
+        use RuleError::FileError;
+
+        for entry in fs::read_dir(dir).map_err(FileError)? {
+            let path = entry.map_err(FileError)?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                self.load_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), RuleError> {
+        let file = File::open(path).map_err(RuleError::FileError)?;
+
+        self.load(BufReader::new(file))
+    }
+
+    /// Loads a single rule or an array of rules.
+    pub fn load<R: Read>(&mut self, r: R) -> Result<(), RuleError> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Rules {
+            Many(Vec<Rule>),
+            One(Box<Rule>),
+        }
+
+        let rules = match serde_json::from_reader(r).map_err(RuleError::ParsingError)? {
+            Rules::Many(rules) => rules,
+            Rules::One(rule) => vec![*rule],
+        };
+
+        // Nothing is loaded if any rule is malformed.
+        if let Some(rule) = rules.iter().find(|rule| rule.version_key().is_none()) {
+            return Err(invalid_version(rule));
+        }
+
+        log::debug!(target:"dgc", "Loaded {} business rules.", rules.len());
+
+        for rule in rules {
+            self.insert(rule)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a rule, replacing the same version of it if already loaded.
+    pub fn insert(&mut self, rule: Rule) -> Result<(), RuleError> {
+        if rule.version_key().is_none() {
+            return Err(invalid_version(&rule));
+        }
+
+        let versions = self.rules.entry(rule.identifier.clone()).or_default();
+
+        versions.retain(|version| version.version != rule.version);
+        versions.push(rule);
+
+        Ok(())
+    }
+
+    /// Number of rules, every version included.
+    pub fn len(&self) -> usize {
+        self.rules.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.rules.values().flatten()
+    }
+
+    /// Acceptance rules of the arrival country and invalidation rules of the
+    /// issuing country, for this type of certificate, in the latest version
+    /// valid at `at`. Rules specific to a region are left out.
+    pub fn applicable(
+        &self,
+        arrival_country: &str,
+        issuer_country: &str,
+        certificate_type: RuleCertificateType,
+        at: DateTime<FixedOffset>,
+    ) -> Vec<&Rule> {
+        let mut rules: Vec<&Rule> = self
+            .rules
+            .values()
+            .filter_map(|versions| {
+                versions
+                    .iter()
+                    .filter(|rule| rule.is_valid_at(at))
+                    .max_by_key(|rule| rule.version_key())
+            })
+            .filter(|rule| match rule.rule_type {
+                RuleType::Acceptance => rule.country == arrival_country,
+                RuleType::Invalidation => rule.country == issuer_country,
+            })
+            .filter(|rule| rule.region.is_none())
+            .filter(|rule| {
+                rule.certificate_type == RuleCertificateType::General
+                    || rule.certificate_type == certificate_type
+            })
+            .collect();
+
+        rules.sort_by(|a, b| a.identifier.cmp(&b.identifier));
+
+        rules
+    }
+
+    /// Evaluates the rules applicable to `payload`, with the arrival country,
+    /// issuing country and date found in `external`.
+    pub fn validate(
+        &self,
+        payload: &HCertPayload,
+        external: &ExternalParameters,
+    ) -> Result<Vec<RuleResult>, CertLogicError> {
+        let certificate_type = payload
+            .kind()
+            .map(|kind| RuleCertificateType::of(&kind))
+            .unwrap_or(RuleCertificateType::General);
+
+        let data = data(payload, external)?;
+
+        let results = self
+            .applicable(
+                &external.country_code,
+                &external.issuer_country_code,
+                certificate_type,
+                external.validation_clock,
+            )
+            .into_iter()
+            .map(|rule| RuleResult {
+                rule,
+                outcome: rule.evaluate(&data),
+            })
+            .collect();
+
+        Ok(results)
+    }
+}
+
+fn invalid_version(rule: &Rule) -> RuleError {
+    RuleError::InvalidVersion {
+        identifier: rule.identifier.clone(),
+        version: rule.version.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_cbor::Value as Cbor;
+    use serde_json::json;
+
+    use super::*;
+    use crate::valueset::ValueSets;
+
+    fn at(date_time: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(date_time).unwrap()
+    }
+
+    fn rule(identifier: &str, rule_type: &str, country: &str, certificate_type: &str) -> Json {
+        json!({
+            "Identifier": identifier,
+            "Type": rule_type,
+            "Country": country,
+            "Version": "1.0.0",
+            "Engine": "CERTLOGIC",
+            "CertificateType": certificate_type,
+            "Description": [{ "lang": "en", "desc": identifier }],
+            "ValidFrom": "2021-07-01T00:00:00Z",
+            "ValidTo": "2030-01-01T00:00:00Z",
+            "Logic": true
+        })
+    }
+
+    fn rule_set(rules: Vec<Json>) -> RuleSet {
+        let mut set = RuleSet::new();
+        set.load(Json::Array(rules).to_string().as_bytes()).unwrap();
+        set
+    }
+
+    fn identifiers(rules: Vec<&Rule>) -> Vec<(&str, &str)> {
+        rules
+            .iter()
+            .map(|rule| (rule.identifier.as_str(), rule.version.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn selects_the_latest_version_valid_at_the_date() {
+        let mut versions = vec![];
+
+        for (version, valid_from) in [
+            ("1.0.0", "2021-07-01T00:00:00Z"),
+            ("1.9.0", "2021-08-01T00:00:00Z"),
+            ("1.10.0", "2021-09-01T00:00:00Z"),
+            ("2.0.0", "2025-01-01T00:00:00Z"),
+        ] {
+            let mut rule = rule("VR-FR-0001", "Acceptance", "FR", "Vaccination");
+            rule["Version"] = json!(version);
+            rule["ValidFrom"] = json!(valid_from);
+            versions.push(rule);
+        }
+
+        let set = rule_set(versions);
+        let applicable = |date_time| {
+            identifiers(set.applicable("FR", "DE", RuleCertificateType::Vaccination, at(date_time)))
+        };
+
+        assert_eq!(set.len(), 4);
+        assert_eq!(applicable("2021-06-01T00:00:00Z"), vec![]);
+        assert_eq!(
+            applicable("2021-08-15T00:00:00Z"),
+            vec![("VR-FR-0001", "1.9.0")]
+        );
+        assert_eq!(
+            applicable("2022-01-01T00:00:00Z"),
+            vec![("VR-FR-0001", "1.10.0")]
+        );
+        assert_eq!(
+            applicable("2025-01-01T00:00:00Z"),
+            vec![("VR-FR-0001", "2.0.0")]
+        );
+        assert_eq!(applicable("2030-01-01T00:00:00Z"), vec![]);
+    }
+
+    #[test]
+    fn selects_acceptance_rules_of_the_arrival_country_and_invalidation_rules_of_the_issuer() {
+        let set = rule_set(vec![
+            rule("GR-FR-0001", "Acceptance", "FR", "General"),
+            rule("GR-DE-0001", "Acceptance", "DE", "General"),
+            rule("IR-FR-0001", "Invalidation", "FR", "General"),
+            rule("IR-DE-0001", "Invalidation", "DE", "General"),
+        ]);
+
+        let applicable = set.applicable(
+            "FR",
+            "DE",
+            RuleCertificateType::Test,
+            at("2022-01-01T00:00:00Z"),
+        );
+
+        assert_eq!(
+            identifiers(applicable),
+            vec![("GR-FR-0001", "1.0.0"), ("IR-DE-0001", "1.0.0")]
+        );
+    }
+
+    #[test]
+    fn selects_general_rules_and_those_of_the_certificate_type() {
+        let set = rule_set(vec![
+            rule("GR-FR-0001", "Acceptance", "FR", "General"),
+            rule("RR-FR-0001", "Acceptance", "FR", "Recovery"),
+            rule("TR-FR-0001", "Acceptance", "FR", "Test"),
+            rule("VR-FR-0001", "Acceptance", "FR", "Vaccination"),
+        ]);
+
+        let applicable = |certificate_type| {
+            identifiers(set.applicable("FR", "FR", certificate_type, at("2022-01-01T00:00:00Z")))
+        };
+
+        assert_eq!(
+            applicable(RuleCertificateType::Test),
+            vec![("GR-FR-0001", "1.0.0"), ("TR-FR-0001", "1.0.0")]
+        );
+        assert_eq!(
+            applicable(RuleCertificateType::General),
+            vec![("GR-FR-0001", "1.0.0")]
+        );
+    }
+
+    #[test]
+    fn leaves_regional_rules_out() {
+        let mut regional = rule("GR-DE-0002", "Acceptance", "DE", "General");
+        regional["Region"] = json!("BW");
+
+        let set = rule_set(vec![
+            rule("GR-DE-0001", "Acceptance", "DE", "General"),
+            regional,
+        ]);
+
+        let applicable = set.applicable(
+            "DE",
+            "FR",
+            RuleCertificateType::Vaccination,
+            at("2022-01-01T00:00:00Z"),
+        );
+
+        assert_eq!(identifiers(applicable), vec![("GR-DE-0001", "1.0.0")]);
+    }
+
+    #[test]
+    fn rejects_malformed_versions() {
+        for version in ["1.0.x", "1..0", "+1.0.0", "1.0.0-rc1", ""] {
+            let mut malformed = rule("GR-FR-0002", "Acceptance", "FR", "General");
+            malformed["Version"] = json!(version);
+
+            let rules = json!([rule("GR-FR-0001", "Acceptance", "FR", "General"), malformed]);
+
+            let mut set = RuleSet::new();
+
+            assert!(matches!(
+                set.load(rules.to_string().as_bytes()),
+                Err(RuleError::InvalidVersion { identifier, .. }) if identifier == "GR-FR-0002"
+            ));
+            assert!(set.is_empty());
+        }
+    }
+
+    #[test]
+    fn rules_see_the_key_id() {
+        let mut kid_rule = rule("GR-FR-0001", "Acceptance", "FR", "General");
+        kid_rule["Logic"] = json!({ "===": [{ "var": "external.kid" }, "AAECAwQFBgc="] });
+
+        let set = rule_set(vec![kid_rule]);
+
+        let dcc = json!({
+            "ver": "1.3.0",
+            "dob": "1990-01-01",
+            "nam": { "fnt": "DUPONT" },
+            "t": [{
+                "tg": "840539006",
+                "tt": "LP6464-4",
+                "sc": "2021-12-01T10:00:00Z",
+                "tr": "260415000",
+                "co": "FR",
+                "is": "Ministère de la Santé",
+                "ci": "URN:UVCI:01:FR:W7V2BE46QSBJ#L"
+            }]
+        });
+
+        let hcert = Cbor::Map(
+            [(Cbor::Integer(1), serde_cbor::value::to_value(dcc).unwrap())]
+                .into_iter()
+                .collect(),
+        );
+        let claims = Cbor::Map(
+            [
+                (Cbor::Integer(1), Cbor::Text("FR".into())),
+                (Cbor::Integer(4), Cbor::Integer(1_700_000_000)),
+                (Cbor::Integer(6), Cbor::Integer(1_600_000_000)),
+                (Cbor::Integer(-260), hcert),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let buf = serde_cbor::to_vec(&claims).unwrap();
+        let payload: HCertPayload = serde_cbor::from_slice(&buf).unwrap();
+
+        let value_sets = ValueSets::bundled();
+        let validate = |kid| {
+            let external = ExternalParameters::new(
+                &payload,
+                kid,
+                at("2022-01-01T00:00:00Z"),
+                "FR",
+                &value_sets,
+            );
+
+            set.validate(&payload, &external).unwrap()[0].passed()
+        };
+
+        assert!(validate(Some("AAECAwQFBgc=")));
+        assert!(!validate(Some("BwYFBAMCAQA=")));
+        assert!(!validate(None));
+    }
+}
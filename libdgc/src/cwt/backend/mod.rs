@@ -0,0 +1,173 @@
+use libkeystore::VerificationKey;
+
+#[cfg(feature = "ring")]
+mod ring;
+#[cfg(feature = "rustcrypto")]
+mod rustcrypto;
+
+#[cfg(feature = "ring")]
+pub use self::ring::RingBackend;
+#[cfg(feature = "rustcrypto")]
+pub use self::rustcrypto::RustCryptoBackend;
+
+/// Backend used when none is specified: `ring` when enabled, RustCrypto
+/// otherwise.
+#[cfg(feature = "ring")]
+pub type DefaultBackend = RingBackend;
+#[cfg(all(feature = "rustcrypto", not(feature = "ring")))]
+pub type DefaultBackend = RustCryptoBackend;
+
+#[cfg(not(any(feature = "ring", feature = "rustcrypto")))]
+compile_error!("At least one of the `ring` or `rustcrypto` features must be enabled.");
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BackendError {
+    UnsupportedAlgorithm,
+    InvalidKey,
+    /// The signature could not be parsed
+    MalformedSignature,
+    /// The signature does not match the message and key
+    InvalidSignature,
+}
+
+/// Cryptographic primitives signature verification relies on.
+pub trait CryptoBackend {
+    /// Verifies a DER encoded ECDSA `signature` of `message`, hashed with the
+    /// digest matching the curve of `key`.
+    fn verify_ecdsa(
+        &self,
+        key: &VerificationKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), BackendError>;
+
+    /// Verifies an RSASSA-PSS `signature` of `message`, with SHA-256 and a
+    /// salt as long as the digest (PS256).
+    fn verify_rsa_pss(
+        &self,
+        key: &VerificationKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), BackendError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use libkeystore::PublicKey;
+
+    use super::*;
+    use crate::{
+        cose::COSE_Sign1,
+        cwt::{verify_signature_with_key, SignatureMode, VerificationError},
+    };
+
+    /// COSE_Sign1 messages signed with each algorithm, and the SPKI of the
+    /// key that signed them.
+    macro_rules! fixture {
+        ($name:literal) => {
+            (
+                &include_bytes!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/cwt/",
+                    $name,
+                    ".cose"
+                ))[..],
+                &include_bytes!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/cwt/",
+                    $name,
+                    ".pem"
+                ))[..],
+            )
+        };
+    }
+
+    fn verify<B: CryptoBackend>(
+        backend: &B,
+        (cose, pem): (&[u8], &[u8]),
+    ) -> Result<(), VerificationError> {
+        let cose_msg: COSE_Sign1 = serde_cbor::from_slice(cose).expect("Invalid COSE fixture.");
+        let key = PublicKey::from_pem(pem).expect("Invalid key fixture.");
+
+        verify_signature_with_key(&cose_msg, &key, SignatureMode::Strict, backend).map(|_| ())
+    }
+
+    /// Flips a bit of the last byte of the message, which is part of the
+    /// signature.
+    fn assert_rejected<B: CryptoBackend>(backend: &B, (cose, pem): (&[u8], &[u8])) {
+        let mut cose = cose.to_vec();
+
+        if let Some(last) = cose.last_mut() {
+            *last ^= 0x01;
+        }
+
+        assert!(matches!(
+            verify(backend, (&cose, pem)),
+            Err(VerificationError::InvalidSignature(
+                BackendError::InvalidSignature
+            ))
+        ));
+    }
+
+    macro_rules! backend_tests {
+        ($module:ident, $backend:expr) => {
+            mod $module {
+                use super::*;
+
+                #[test]
+                fn verifies_es256() {
+                    assert!(verify(&$backend, fixture!("es256")).is_ok());
+                }
+
+                #[test]
+                fn verifies_es384() {
+                    assert!(verify(&$backend, fixture!("es384")).is_ok());
+                }
+
+                #[test]
+                fn verifies_ps256() {
+                    assert!(verify(&$backend, fixture!("ps256")).is_ok());
+                }
+
+                #[test]
+                fn rejects_tampered_es256() {
+                    assert_rejected(&$backend, fixture!("es256"));
+                }
+
+                #[test]
+                fn rejects_tampered_es384() {
+                    assert_rejected(&$backend, fixture!("es384"));
+                }
+
+                #[test]
+                fn rejects_tampered_ps256() {
+                    assert_rejected(&$backend, fixture!("ps256"));
+                }
+            }
+        };
+    }
+
+    #[cfg(feature = "ring")]
+    backend_tests!(ring, RingBackend);
+
+    #[cfg(feature = "rustcrypto")]
+    backend_tests!(rustcrypto, RustCryptoBackend);
+
+    #[cfg(feature = "ring")]
+    #[test]
+    fn ring_does_not_support_p521() {
+        assert!(matches!(
+            verify(&RingBackend, fixture!("es512")),
+            Err(VerificationError::UnsupportedAlgorithm(
+                libkeystore::KeyAlgorithm::EcdsaP521
+            ))
+        ));
+    }
+
+    #[cfg(feature = "rustcrypto")]
+    #[test]
+    fn rustcrypto_verifies_es512() {
+        assert!(verify(&RustCryptoBackend, fixture!("es512")).is_ok());
+        assert_rejected(&RustCryptoBackend, fixture!("es512"));
+    }
+}
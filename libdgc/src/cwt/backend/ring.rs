@@ -0,0 +1,45 @@
+use libkeystore::{KeyAlgorithm, VerificationKey};
+use ring::signature::{
+    UnparsedPublicKey, ECDSA_P256_SHA256_ASN1, ECDSA_P384_SHA384_ASN1, RSA_PSS_2048_8192_SHA256,
+};
+
+use super::{BackendError, CryptoBackend};
+
+/// Verification backed by `ring`; P-521 is not supported.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RingBackend;
+
+impl CryptoBackend for RingBackend {
+    fn verify_ecdsa(
+        &self,
+        key: &VerificationKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), BackendError> {
+        let algorithm = match key.algorithm {
+            KeyAlgorithm::EcdsaP256 => &ECDSA_P256_SHA256_ASN1,
+            KeyAlgorithm::EcdsaP384 => &ECDSA_P384_SHA384_ASN1,
+
+            _ => return Err(BackendError::UnsupportedAlgorithm),
+        };
+
+        UnparsedPublicKey::new(algorithm, &key.public_key)
+            .verify(message, signature)
+            .map_err(|_| BackendError::InvalidSignature)
+    }
+
+    fn verify_rsa_pss(
+        &self,
+        key: &VerificationKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), BackendError> {
+        if key.algorithm != KeyAlgorithm::Rsa {
+            return Err(BackendError::UnsupportedAlgorithm);
+        }
+
+        UnparsedPublicKey::new(&RSA_PSS_2048_8192_SHA256, &key.public_key)
+            .verify(message, signature)
+            .map_err(|_| BackendError::InvalidSignature)
+    }
+}
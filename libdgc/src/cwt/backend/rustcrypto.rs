@@ -0,0 +1,64 @@
+use libkeystore::{KeyAlgorithm, VerificationKey};
+use p256::ecdsa::signature::Verifier;
+use rsa::{pkcs1::DecodeRsaPublicKey, pss, RsaPublicKey};
+use sha2::Sha256;
+
+use super::{BackendError, CryptoBackend};
+
+/// Pure-Rust verification backed by the RustCrypto elliptic curve crates.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RustCryptoBackend;
+
+macro_rules! verify_with {
+    ($curve:ident, $key:expr, $message:expr, $signature:expr) => {{
+        let verifying_key = $curve::ecdsa::VerifyingKey::from_sec1_bytes($key)
+            .map_err(|_| BackendError::InvalidKey)?;
+
+        let signature = $curve::ecdsa::Signature::from_der($signature)
+            .map_err(|_| BackendError::MalformedSignature)?;
+
+        verifying_key
+            .verify($message, &signature)
+            .map_err(|_| BackendError::InvalidSignature)
+    }};
+}
+
+impl CryptoBackend for RustCryptoBackend {
+    fn verify_ecdsa(
+        &self,
+        key: &VerificationKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), BackendError> {
+        let public_key = key.public_key.as_slice();
+
+        match key.algorithm {
+            KeyAlgorithm::EcdsaP256 => verify_with!(p256, public_key, message, signature),
+            KeyAlgorithm::EcdsaP384 => verify_with!(p384, public_key, message, signature),
+            KeyAlgorithm::EcdsaP521 => verify_with!(p521, public_key, message, signature),
+
+            _ => Err(BackendError::UnsupportedAlgorithm),
+        }
+    }
+
+    fn verify_rsa_pss(
+        &self,
+        key: &VerificationKey,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), BackendError> {
+        if key.algorithm != KeyAlgorithm::Rsa {
+            return Err(BackendError::UnsupportedAlgorithm);
+        }
+
+        let public_key =
+            RsaPublicKey::from_pkcs1_der(&key.public_key).map_err(|_| BackendError::InvalidKey)?;
+
+        let signature =
+            pss::Signature::try_from(signature).map_err(|_| BackendError::MalformedSignature)?;
+
+        pss::VerifyingKey::<Sha256>::new(public_key)
+            .verify(message, &signature)
+            .map_err(|_| BackendError::InvalidSignature)
+    }
+}
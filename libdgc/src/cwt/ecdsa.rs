@@ -0,0 +1,324 @@
+use asn1_der::{
+    typed::{DerDecodable, DerEncodable, DerTypeView, Sequence, SequenceVec},
+    DerObject, VecBacking,
+};
+
+const INTEGER_TAG: u8 = 0x02;
+
+/// How ECDSA signatures found in COSE messages are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignatureMode {
+    /// Only accept the `r || s` encoding mandated by RFC 8152.
+    #[default]
+    Strict,
+
+    /// Also accept DER encoded signatures, as emitted by some issuers.
+    Lenient,
+}
+
+/// Size in bytes of the curve order of the ECDSA algorithm with COSE id
+/// `alg`: ES256, ES384 or ES512.
+pub(crate) fn component_len(alg: i64) -> Option<usize> {
+    match alg {
+        -7 => Some(32),
+        -35 => Some(48),
+        -36 => Some(66),
+        _ => None,
+    }
+}
+
+/// Converts a COSE ECDSA signature to the DER encoding expected by
+/// verification primitives, `component_len` being the size in bytes of the
+/// curve order (32 for P-256, 48 for P-384, 66 for P-521).
+///
+/// Returns whether the signature was DER encoded to begin with.
+pub(crate) fn signature_to_der(
+    signature: &[u8],
+    component_len: usize,
+    mode: SignatureMode,
+    dest: &mut Vec<u8>,
+) -> Result<bool, &'static str> {
+    let (r, s, was_der) = components(signature, component_len, mode)?;
+
+    let mut r_buf = vec![];
+    let mut s_buf = vec![];
+
+    let r = to_der_integer(&mut r_buf, r).map_err(|_| "Failed to encode `r` to DER.")?;
+    let s = to_der_integer(&mut s_buf, s).map_err(|_| "Failed to encode `s` to DER.")?;
+
+    SequenceVec(vec![r, s])
+        .encode(dest)
+        .map_err(|_| "Failed to encode signature to DER.")?;
+
+    Ok(was_der)
+}
+
+/// `r` of a COSE ECDSA signature, padded to `component_len` bytes as in the
+/// `r || s` encoding, even if the signature was DER encoded.
+pub(crate) fn signature_r(
+    signature: &[u8],
+    component_len: usize,
+    mode: SignatureMode,
+) -> Result<Vec<u8>, &'static str> {
+    let (r, _, _) = components(signature, component_len, mode)?;
+
+    let mut padded = vec![0; component_len - r.len()];
+    padded.extend_from_slice(r);
+
+    Ok(padded)
+}
+
+/// `r` and `s` without leading zeros, and whether they were DER encoded.
+fn components(
+    signature: &[u8],
+    component_len: usize,
+    mode: SignatureMode,
+) -> Result<(&[u8], &[u8], bool), &'static str> {
+    let (r, s, was_der) = if signature.len() == 2 * component_len {
+        let (r, s) = signature.split_at(component_len);
+
+        (r, s, false)
+    } else if mode == SignatureMode::Lenient {
+        let (r, s) = parse_der_signature(signature)?;
+
+        (r, s, true)
+    } else {
+        return Err("Signature length does not match the curve.");
+    };
+
+    let r = strip_leading_zeros(r);
+    let s = strip_leading_zeros(s);
+
+    if r.is_empty() || s.is_empty() {
+        return Err("Signature components cannot be zero.");
+    }
+
+    if r.len() > component_len || s.len() > component_len {
+        return Err("Signature components are too large for the curve.");
+    }
+
+    Ok((r, s, was_der))
+}
+
+fn to_der_integer<'buf>(
+    buffer: &'buf mut Vec<u8>,
+    bytes: &[u8],
+) -> Result<DerObject<'buf>, asn1_der::Asn1DerError> {
+    let mut value = Vec::with_capacity(bytes.len() + 1);
+
+    // Positive integers with their high bit set need a leading zero byte.
+    if bytes[0] & 0x80 > 0 {
+        value.push(0);
+    }
+    value.extend_from_slice(bytes);
+
+    DerObject::new(INTEGER_TAG, &value, VecBacking(buffer))
+}
+
+/// Reads `r` and `s` from a DER `SEQUENCE { INTEGER, INTEGER }`.
+fn parse_der_signature(signature: &[u8]) -> Result<(&[u8], &[u8]), &'static str> {
+    const NOT_DER: &str = "Signature is neither raw nor DER encoded.";
+
+    let sequence = Sequence::decode(signature).map_err(|_| NOT_DER)?;
+
+    let object = sequence.object();
+
+    if object.header().len() + object.value().len() != signature.len() || sequence.len() != 2 {
+        return Err(NOT_DER);
+    }
+
+    let r = sequence.get(0).map_err(|_| NOT_DER)?;
+    let s = sequence.get(1).map_err(|_| NOT_DER)?;
+
+    fn integer_value(integer: DerObject<'_>) -> Option<&[u8]> {
+        let value = integer.value();
+
+        match value.first() {
+            // Negative integers are not valid signature components.
+            Some(first) if integer.tag() == INTEGER_TAG && first & 0x80 == 0 => Some(value),
+            _ => None,
+        }
+    }
+
+    let r = integer_value(r).ok_or(NOT_DER)?;
+    let s = integer_value(s).ok_or(NOT_DER)?;
+
+    Ok((r, s))
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let first_non_zero = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+
+    &bytes[first_non_zero..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// DER encoding of positive integers `r` and `s`, given in their minimal
+    /// form.
+    fn der(r: &[u8], s: &[u8]) -> Vec<u8> {
+        let integer = |bytes: &[u8]| {
+            let mut value = vec![];
+
+            if bytes[0] & 0x80 > 0 {
+                value.push(0);
+            }
+            value.extend_from_slice(bytes);
+
+            let mut integer = vec![INTEGER_TAG, value.len() as u8];
+            integer.extend(value);
+            integer
+        };
+
+        let content = [integer(r), integer(s)].concat();
+
+        let mut sequence = vec![0x30];
+        if content.len() > 0x7f {
+            sequence.push(0x81);
+        }
+        sequence.push(content.len() as u8);
+        sequence.extend(content);
+        sequence
+    }
+
+    fn convert(
+        signature: &[u8],
+        component_len: usize,
+        mode: SignatureMode,
+    ) -> Result<(Vec<u8>, bool), &'static str> {
+        let mut dest = vec![];
+
+        signature_to_der(signature, component_len, mode, &mut dest).map(|was_der| (dest, was_der))
+    }
+
+    #[test]
+    fn raw_signatures_of_every_curve() {
+        for component_len in [32, 48, 66] {
+            let r = vec![0x12; component_len];
+            let s = vec![0x34; component_len];
+
+            let raw = [r.as_slice(), s.as_slice()].concat();
+
+            for mode in [SignatureMode::Strict, SignatureMode::Lenient] {
+                assert_eq!(convert(&raw, component_len, mode), Ok((der(&r, &s), false)));
+            }
+        }
+    }
+
+    #[test]
+    fn high_bit_gets_a_zero_byte() {
+        let raw = [vec![0x80; 32], vec![0xff; 32]].concat();
+
+        let (signature, _) = convert(&raw, 32, SignatureMode::Strict).unwrap();
+
+        assert_eq!(&signature[2..5], &[INTEGER_TAG, 33, 0x00]);
+        assert_eq!(signature, der(&[0x80; 32], &[0xff; 32]));
+    }
+
+    #[test]
+    fn leading_zeros_are_stripped() {
+        let mut r = vec![0x00; 32];
+        r[2..].copy_from_slice(&[0x01; 30]);
+
+        let mut s = vec![0x00; 32];
+        s[31] = 0x80;
+
+        let raw = [r, s].concat();
+
+        assert_eq!(
+            convert(&raw, 32, SignatureMode::Strict),
+            Ok((der(&[0x01; 30], &[0x80]), false))
+        );
+    }
+
+    #[test]
+    fn der_only_accepted_in_lenient_mode() {
+        let signature = der(&[0x56; 32], &[0x78; 31]);
+
+        assert_eq!(
+            convert(&signature, 32, SignatureMode::Lenient),
+            Ok((signature.clone(), true))
+        );
+        assert!(convert(&signature, 32, SignatureMode::Strict).is_err());
+    }
+
+    #[test]
+    fn der_with_padded_integers_is_normalized() {
+        let padded = [
+            &[0x30, 0x0a][..],
+            &[INTEGER_TAG, 0x03, 0x00, 0x00, 0x01],
+            &[INTEGER_TAG, 0x03, 0x00, 0x00, 0x02],
+        ]
+        .concat();
+
+        assert_eq!(
+            convert(&padded, 32, SignatureMode::Lenient),
+            Ok((der(&[0x01], &[0x02]), true))
+        );
+    }
+
+    #[test]
+    fn der_with_trailing_data_is_rejected() {
+        let mut signature = der(&[0x56; 32], &[0x78; 32]);
+        signature.push(0x00);
+
+        assert!(convert(&signature, 32, SignatureMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn negative_der_integers_are_rejected() {
+        let signature = [0x30, 0x06, INTEGER_TAG, 0x01, 0x80, INTEGER_TAG, 0x01, 0x01];
+
+        assert!(convert(&signature, 32, SignatureMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn zero_components_are_rejected() {
+        let zero_r = [vec![0x00; 32], vec![0x01; 32]].concat();
+        let zero_s = [vec![0x01; 32], vec![0x00; 32]].concat();
+
+        assert!(convert(&zero_r, 32, SignatureMode::Strict).is_err());
+        assert!(convert(&zero_s, 32, SignatureMode::Strict).is_err());
+
+        let der_zero = [0x30, 0x06, INTEGER_TAG, 0x01, 0x00, INTEGER_TAG, 0x01, 0x01];
+
+        assert!(convert(&der_zero, 32, SignatureMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn oversize_components_are_rejected() {
+        let signature = der(&[0x01; 33], &[0x01; 32]);
+
+        assert!(convert(&signature, 32, SignatureMode::Lenient).is_err());
+
+        // Raw signatures of a larger curve do not match the length either.
+        let raw = vec![0x01; 96];
+
+        assert!(convert(&raw, 32, SignatureMode::Strict).is_err());
+        assert!(convert(&raw, 32, SignatureMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn r_is_padded_to_the_curve_size() {
+        let mut r = vec![0x00; 32];
+        r[1..].copy_from_slice(&[0x9a; 31]);
+
+        let raw = [r.clone(), vec![0x01; 32]].concat();
+        let signature = der(&[0x9a; 31], &[0x01; 32]);
+
+        assert_eq!(signature_r(&raw, 32, SignatureMode::Strict), Ok(r.clone()));
+        assert_eq!(signature_r(&signature, 32, SignatureMode::Lenient), Ok(r));
+        assert!(signature_r(&signature, 32, SignatureMode::Strict).is_err());
+    }
+
+    #[test]
+    fn malformed_lengths_do_not_panic() {
+        for signature in [&[][..], &[0x01], &[0x30], &[0x30, 0x00], &[0x01; 63]] {
+            for mode in [SignatureMode::Strict, SignatureMode::Lenient] {
+                assert!(convert(signature, 32, mode).is_err());
+            }
+        }
+    }
+}
@@ -1,18 +1,16 @@
-use libkeystore::{KeyStore, KeystoreError};
+use libkeystore::{KeyAlgorithm, KeyStore, KeystoreError, PublicKey, VerificationKey};
 use serde_cbor::{self, error::Error as CBORError};
 
+pub mod backend;
+pub(crate) mod ecdsa;
 mod sign;
 
-use webpki::ECDSA_P256_SHA256;
+pub use backend::{BackendError, CryptoBackend, DefaultBackend};
+pub use ecdsa::SignatureMode;
 
 use crate::COSE_Sign1;
 use crate::Generic_Headers;
 
-use asn1_der::{
-    typed::{DerEncodable, SequenceVec},
-    DerObject, VecBacking,
-};
-
 #[derive(Debug)]
 pub enum VerificationError {
     DecodingFailed(CBORError),
@@ -20,13 +18,66 @@ pub enum VerificationError {
     PubKeyNotFoundOrInvalid(KeystoreError),
     BadCertificate,
     BadSignature,
-    InvalidSignature(webpki::Error),
+    InvalidSignature(BackendError),
+    UnsupportedAlgorithm(KeyAlgorithm),
+
+    /// The algorithm of the protected header, if any, is not one the key
+    /// can be used with.
+    AlgorithmMismatch {
+        alg: Option<i64>,
+        key: KeyAlgorithm,
+    },
+}
+
+#[derive(Debug)]
+pub struct SignatureReport {
+    pub kid: String,
+
+    /// The signature was DER encoded instead of `r || s`, which is only
+    /// accepted in lenient mode.
+    pub der_encoded_signature: bool,
 }
 
-pub fn verify_signature(
+/// How the key id of a certificate relates to an explicitly provided key.
+#[derive(Debug, PartialEq, Eq)]
+pub enum KidMatch {
+    Matched,
+    Mismatched {
+        expected: String,
+    },
+
+    /// The certificate has no key id, or the provided key is not a
+    /// certificate a key id could be computed from.
+    Unknown,
+}
+
+#[derive(Debug)]
+pub struct ExplicitKeyReport {
+    /// Key id found in the protected header, if any
+    pub kid: Option<String>,
+
+    pub kid_match: KidMatch,
+
+    /// The signature was DER encoded instead of `r || s`, which is only
+    /// accepted in lenient mode.
+    pub der_encoded_signature: bool,
+}
+
+pub fn verify_signature<B: CryptoBackend>(
     cose_obj: &COSE_Sign1,
     keystore: &KeyStore,
+    backend: &B,
 ) -> Result<String, VerificationError> {
+    verify_signature_with_mode(cose_obj, keystore, SignatureMode::Strict, backend)
+        .map(|report| report.kid)
+}
+
+pub fn verify_signature_with_mode<B: CryptoBackend>(
+    cose_obj: &COSE_Sign1,
+    keystore: &KeyStore,
+    mode: SignatureMode,
+    backend: &B,
+) -> Result<SignatureReport, VerificationError> {
     use VerificationError::*;
 
     let signature = &cose_obj.signature;
@@ -41,54 +92,244 @@ pub fn verify_signature(
 
     let validation_data = sign::get_validation_data(cose_obj.protected, cose_obj.payload);
 
-    let cert = keystore
-        .pubkey_for_signature(&kid)
+    let key = keystore
+        .verification_key(&kid)
         .map_err(PubKeyNotFoundOrInvalid)?;
 
+    let der_encoded_signature = verify_with_key(
+        key,
+        protected_hdr.alg,
+        &validation_data,
+        signature,
+        mode,
+        backend,
+    )?;
+
+    Ok(SignatureReport {
+        kid,
+        der_encoded_signature,
+    })
+}
+
+/// Verifies the signature with `key`, bypassing any keystore lookup.
+pub fn verify_signature_with_key<B: CryptoBackend>(
+    cose_obj: &COSE_Sign1,
+    key: &PublicKey,
+    mode: SignatureMode,
+    backend: &B,
+) -> Result<ExplicitKeyReport, VerificationError> {
+    use VerificationError::*;
+
+    let protected_hdr: Generic_Headers =
+        serde_cbor::from_slice(cose_obj.protected).map_err(DecodingFailed)?;
+
+    let kid = protected_hdr.kid.map(base64::encode);
+
+    log::debug!(target:"dgc", "Certificate key id: {:?}", kid);
+    log::debug!(target:"dgc", "With algoritm: {:?}", protected_hdr.alg);
+
+    let validation_data = sign::get_validation_data(cose_obj.protected, cose_obj.payload);
+
+    let verification_key = key.verification_key().map_err(PubKeyNotFoundOrInvalid)?;
+
+    let der_encoded_signature = verify_with_key(
+        &verification_key,
+        protected_hdr.alg,
+        &validation_data,
+        cose_obj.signature,
+        mode,
+        backend,
+    )?;
+
+    let kid_match = match (&kid, key.kid()) {
+        (Some(kid), Some(expected)) if *kid == expected => KidMatch::Matched,
+        (Some(_), Some(expected)) => KidMatch::Mismatched { expected },
+        _ => KidMatch::Unknown,
+    };
+
+    Ok(ExplicitKeyReport {
+        kid,
+        kid_match,
+        der_encoded_signature,
+    })
+}
+
+/// Algorithm of the keys the COSE algorithm `alg` is used with.
+pub(crate) fn key_algorithm(alg: i64) -> Option<KeyAlgorithm> {
+    match alg {
+        -7 => Some(KeyAlgorithm::EcdsaP256),
+        -35 => Some(KeyAlgorithm::EcdsaP384),
+        -36 => Some(KeyAlgorithm::EcdsaP521),
+        -37 => Some(KeyAlgorithm::Rsa),
+        _ => None,
+    }
+}
+
+/// Returns whether the signature had to be read as DER.
+fn verify_with_key<B: CryptoBackend>(
+    key: &VerificationKey,
+    alg: Option<i64>,
+    validation_data: &[u8],
+    signature: &[u8],
+    mode: SignatureMode,
+    backend: &B,
+) -> Result<bool, VerificationError> {
+    use VerificationError::*;
+
+    let to_verification_error = |e| match e {
+        BackendError::UnsupportedAlgorithm => UnsupportedAlgorithm(key.algorithm),
+        BackendError::InvalidKey => BadCertificate,
+        e => InvalidSignature(e),
+    };
+
+    if key.algorithm == KeyAlgorithm::Unsupported {
+        return Err(UnsupportedAlgorithm(key.algorithm));
+    }
+
+    if alg.and_then(key_algorithm) != Some(key.algorithm) {
+        return Err(AlgorithmMismatch {
+            alg,
+            key: key.algorithm,
+        });
+    }
+
+    // Read from the header like the revocation hash of the signature, which
+    // the check above makes agree with the key.
+    let component_len = match alg.and_then(ecdsa::component_len) {
+        Some(component_len) => component_len,
+        None => {
+            backend
+                .verify_rsa_pss(key, validation_data, signature)
+                .map_err(to_verification_error)?;
+
+            return Ok(false);
+        }
+    };
+
     let mut signature_der = vec![];
 
-    signature_to_der(signature, &mut signature_der)
-        .ok()
-        .ok_or(BadSignature)?;
+    let was_der = ecdsa::signature_to_der(signature, component_len, mode, &mut signature_der)
+        .map_err(|e| {
+            log::debug!(target:"dgc", "Malformed signature: {}", e);
+
+            BadSignature
+        })?;
+
+    if was_der {
+        log::warn!(target:"dgc", "Issuer emitted a DER encoded signature.");
+    }
 
-    cert.verify_signature(&ECDSA_P256_SHA256, &validation_data, &signature_der)
-        .map_err(InvalidSignature)?;
+    backend
+        .verify_ecdsa(key, validation_data, &signature_der)
+        .map_err(to_verification_error)?;
 
-    Ok(kid)
+    Ok(was_der)
 }
 
-fn signature_to_der(raw_signature: &[u8], dest: &mut Vec<u8>) -> Result<(), &'static str> {
-    let len = raw_signature.len() / 2;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let mut r_bufs = (vec![], raw_signature[..len].to_vec());
-    let mut s_bufs = (vec![], raw_signature[len..].to_vec());
+    macro_rules! fixture {
+        ($name:literal) => {
+            &include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cwt/", $name))[..]
+        };
+    }
+
+    /// Key id of `certificate.der`
+    const KID: &str = "LbAwOnSzmpE=";
 
-    fn to_der_integer<'buf>(
-        buffer: &'buf mut Vec<u8>,
-        bytes: &mut Vec<u8>,
-    ) -> Result<DerObject<'buf>, asn1_der::Asn1DerError> {
-        let backing_vec = VecBacking(buffer);
+    fn verify(cose: &[u8], key: &PublicKey) -> Result<ExplicitKeyReport, VerificationError> {
+        let cose_msg: COSE_Sign1 = serde_cbor::from_slice(cose).expect("Invalid COSE fixture.");
+
+        verify_signature_with_key(
+            &cose_msg,
+            key,
+            SignatureMode::Strict,
+            &DefaultBackend::default(),
+        )
+    }
 
-        if bytes[0] & 0x80 > 0 {
-            let prefix = [0, bytes[0]];
-            bytes.splice(..1, prefix);
+    fn keys() -> [PublicKey; 4] {
+        [
+            PublicKey::from_pem(fixture!("certificate.pem")).unwrap(),
+            PublicKey::from_der(fixture!("certificate.der").to_vec()).unwrap(),
+            PublicKey::from_pem(fixture!("certificate-spki.pem")).unwrap(),
+            PublicKey::from_der(fixture!("certificate-spki.der").to_vec()).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn verifies_with_every_form_of_key() {
+        let [pem, der, spki_pem, spki_der] = keys();
+
+        for certificate in [pem, der] {
+            let report = verify(fixture!("certificate.cose"), &certificate).unwrap();
+
+            assert_eq!(report.kid.as_deref(), Some(KID));
+            assert_eq!(report.kid_match, KidMatch::Matched);
+            assert!(!report.der_encoded_signature);
         }
 
-        asn1_der::DerObject::new(0x02, bytes, backing_vec)
+        // Without a certificate, there is nothing to compute a key id from.
+        for spki in [spki_pem, spki_der] {
+            let report = verify(fixture!("certificate.cose"), &spki).unwrap();
+
+            assert_eq!(report.kid.as_deref(), Some(KID));
+            assert_eq!(report.kid_match, KidMatch::Unknown);
+        }
+    }
+
+    #[test]
+    fn reports_mismatched_kids() {
+        let [certificate, ..] = keys();
+
+        let report = verify(fixture!("certificate-other-kid.cose"), &certificate).unwrap();
+
+        assert_eq!(report.kid.as_deref(), Some("AAAAAAAAAAA="));
+        assert_eq!(
+            report.kid_match,
+            KidMatch::Mismatched {
+                expected: KID.to_owned()
+            }
+        );
     }
 
-    let r = to_der_integer(&mut r_bufs.0, &mut r_bufs.1)
-        .ok()
-        .ok_or("Failed to encode `r` to DER.")?;
+    #[test]
+    fn reports_missing_kids() {
+        let [certificate, ..] = keys();
+
+        let report = verify(fixture!("certificate-no-kid.cose"), &certificate).unwrap();
 
-    let s = to_der_integer(&mut s_bufs.0, &mut s_bufs.1)
-        .ok()
-        .ok_or("Failed to encode `s` to DER.")?;
+        assert_eq!(report.kid, None);
+        assert_eq!(report.kid_match, KidMatch::Unknown);
+    }
 
-    let sequence = SequenceVec(vec![r, s]);
+    #[test]
+    fn rejects_other_keys() {
+        let other = PublicKey::from_pem(fixture!("es256.pem")).unwrap();
+
+        assert!(matches!(
+            verify(fixture!("certificate.cose"), &other),
+            Err(VerificationError::InvalidSignature(_))
+        ));
+    }
 
-    sequence
-        .encode(dest)
-        .ok()
-        .ok_or("Failed to encode signature to DER.")
+    #[test]
+    fn rejects_algorithms_disagreeing_with_the_key() {
+        // Signed with a P-384 key, but announcing ES256 in its header
+        let key = PublicKey::from_pem(fixture!("es384-alg-es256.pem")).unwrap();
+
+        assert!(matches!(
+            verify(fixture!("es384-alg-es256.cose"), &key),
+            Err(VerificationError::AlgorithmMismatch {
+                alg: Some(-7),
+                key: KeyAlgorithm::EcdsaP384,
+            })
+        ));
+
+        let key = PublicKey::from_pem(fixture!("es384.pem")).unwrap();
+
+        assert!(verify(fixture!("es384.cose"), &key).is_ok());
+    }
 }
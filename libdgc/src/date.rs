@@ -0,0 +1,233 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    /// Not a `YYYY-MM-DD` date, or not a valid partial date of birth
+    InvalidDate { field: &'static str, value: String },
+
+    /// Not an RFC 3339 date and time
+    InvalidDateTime { field: &'static str, value: String },
+}
+
+/// A date of birth, which the schema allows to be partially or entirely
+/// unknown: `1980-05-01`, `1980-05`, `1980` or empty. Unknown parts written
+/// as `XX` placeholders, e.g. `1980-XX-XX`, are accepted as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartialDate {
+    Unknown,
+    Year(i32),
+    YearMonth(i32, u32),
+    Date(NaiveDate),
+}
+
+impl PartialDate {
+    pub fn year(&self) -> Option<i32> {
+        match self {
+            PartialDate::Unknown => None,
+            PartialDate::Year(year) | PartialDate::YearMonth(year, _) => Some(*year),
+            PartialDate::Date(date) => Some(date.year()),
+        }
+    }
+
+    pub fn month(&self) -> Option<u32> {
+        match self {
+            PartialDate::Unknown | PartialDate::Year(_) => None,
+            PartialDate::YearMonth(_, month) => Some(*month),
+            PartialDate::Date(date) => Some(date.month()),
+        }
+    }
+
+    pub fn day(&self) -> Option<u32> {
+        match self {
+            PartialDate::Date(date) => Some(date.day()),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for PartialDate {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_end_matches("-XX");
+
+        if s.is_empty() || s == "XXXX" {
+            return Ok(PartialDate::Unknown);
+        }
+
+        let mut parts = s.split('-');
+
+        let year = parts
+            .next()
+            .and_then(|year| parse_number(year, 4))
+            .ok_or("Invalid year.")? as i32;
+
+        let month = match parts.next() {
+            Some(month) => parse_number(month, 2)
+                .filter(|month| (1..=12).contains(month))
+                .ok_or("Invalid month.")?,
+            None => return Ok(PartialDate::Year(year)),
+        };
+
+        let day = match parts.next() {
+            Some(day) => parse_number(day, 2).ok_or("Invalid day.")?,
+            None => return Ok(PartialDate::YearMonth(year, month)),
+        };
+
+        if parts.next().is_some() {
+            return Err("Trailing characters after date.");
+        }
+
+        NaiveDate::from_ymd_opt(year, month, day)
+            .map(PartialDate::Date)
+            .ok_or("Invalid day.")
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartialDate::Unknown => Ok(()),
+            PartialDate::Year(year) => write!(f, "{:04}", year),
+            PartialDate::YearMonth(year, month) => write!(f, "{:04}-{:02}", year, month),
+            PartialDate::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+/// Exactly `len` ASCII digits.
+fn parse_number(s: &str, len: usize) -> Option<u32> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+pub(crate) fn parse_date(field: &'static str, value: &str) -> Result<NaiveDate, DateError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| DateError::InvalidDate {
+        field,
+        value: value.to_owned(),
+    })
+}
+
+pub(crate) fn parse_partial_date(
+    field: &'static str,
+    value: &str,
+) -> Result<PartialDate, DateError> {
+    value.parse().map_err(|_| DateError::InvalidDate {
+        field,
+        value: value.to_owned(),
+    })
+}
+
+pub(crate) fn parse_datetime(
+    field: &'static str,
+    value: &str,
+) -> Result<DateTime<FixedOffset>, DateError> {
+    DateTime::parse_from_rfc3339(value).map_err(|_| DateError::InvalidDateTime {
+        field,
+        value: value.to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> PartialDate {
+        PartialDate::Date(NaiveDate::from_ymd_opt(y, m, d).unwrap())
+    }
+
+    #[test]
+    fn parses_partial_dates() {
+        for (value, expected) in [
+            ("1980-05-01", date(1980, 5, 1)),
+            ("1980-05", PartialDate::YearMonth(1980, 5)),
+            ("1980", PartialDate::Year(1980)),
+            ("", PartialDate::Unknown),
+        ] {
+            assert_eq!(parse_partial_date("dob", value), Ok(expected));
+            assert_eq!(expected.to_string(), value);
+        }
+    }
+
+    #[test]
+    fn parses_placeholders() {
+        for (value, expected) in [
+            ("1980-05-XX", PartialDate::YearMonth(1980, 5)),
+            ("1980-XX-XX", PartialDate::Year(1980)),
+            ("1980-XX", PartialDate::Year(1980)),
+            ("XXXX-XX-XX", PartialDate::Unknown),
+        ] {
+            assert_eq!(parse_partial_date("dob", value), Ok(expected));
+        }
+
+        assert!(parse_partial_date("dob", "1980-XX-01").is_err());
+        assert!(parse_partial_date("dob", "XXXX-05-01").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_partial_dates() {
+        for value in [
+            "80",
+            "1980-5",
+            "1980-13",
+            "1980-02-30",
+            "1980-05-01-01",
+            "1980/05/01",
+            "1980-05-01T00:00:00Z",
+        ] {
+            assert_eq!(
+                parse_partial_date("dob", value),
+                Err(DateError::InvalidDate {
+                    field: "dob",
+                    value: value.to_owned(),
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn full_dates_are_required_elsewhere() {
+        assert!(parse_date("dt", "2021-05-01").is_ok());
+        assert!(parse_date("dt", "2021-05").is_err());
+        assert!(parse_date("dt", "2021").is_err());
+    }
+
+    #[test]
+    fn parses_datetimes_with_offsets() {
+        let utc = parse_datetime("sc", "2021-05-01T10:00:00Z").unwrap();
+
+        for value in [
+            "2021-05-01T12:00:00+02:00",
+            "2021-05-01T04:30:00-05:30",
+            "2021-05-01T10:00:00.000Z",
+        ] {
+            assert_eq!(parse_datetime("sc", value), Ok(utc));
+        }
+
+        assert_eq!(
+            parse_datetime("sc", "2021-05-01T12:00:00+02:00")
+                .unwrap()
+                .offset()
+                .local_minus_utc(),
+            2 * 3600
+        );
+    }
+
+    #[test]
+    fn rejects_datetimes_without_offset() {
+        for value in ["2021-05-01T10:00:00", "2021-05-01", "2021-05-01 10:00"] {
+            assert_eq!(
+                parse_datetime("sc", value),
+                Err(DateError::InvalidDateTime {
+                    field: "sc",
+                    value: value.to_owned(),
+                })
+            );
+        }
+    }
+}
@@ -1,6 +1,20 @@
-use libkeystore::KeyStore;
+use chrono::{DateTime, Utc};
+use libkeystore::{KeyStore, PublicKey};
+use serde_cbor::Value;
 
-use crate::hcert::HCertPayload;
+use crate::{
+    cose::Generic_Headers,
+    cwt::{
+        ecdsa, CryptoBackend, DefaultBackend, ExplicitKeyReport, SignatureMode, SignatureReport,
+        VerificationError,
+    },
+    hcert::HCertPayload,
+    revocation::{
+        Blocklist, BlocklistStatus, ChunkMatch, ChunkStore, Revocation, RevocationHashes,
+        RevocationStatus, RevocationStore,
+    },
+    schema::{SchemaValidator, Violation},
+};
 
 use super::{error::DecodeError, Decoded, DigitalGreenCertificate, Verified};
 
@@ -13,13 +27,17 @@ impl<'buf> DigitalGreenCertificate<Decoded<'buf>> {
         &'buf self,
         keystore: &'a KeyStore,
     ) -> Result<(String, DigitalGreenCertificate<Verified<'buf>>), DecodeError<'buf>> {
-        let kid = match crate::cwt::verify_signature(&self.state.cose_msg, keystore) {
+        let kid = match crate::cwt::verify_signature(
+            &self.state.cose_msg,
+            keystore,
+            &DefaultBackend::default(),
+        ) {
             Ok(k) => k,
 
             Err(e) => return Err(DecodeError::InvalidSignature(e, &self.state.cose_msg)),
         };
 
-        let hcert_payload = serde_cbor::from_slice(self.state.cose_msg.payload)?; //Failed to decode CWT payload.
+        let hcert_payload = self.verified_payload()?;
 
         let result = DigitalGreenCertificate {
             state: Verified { hcert_payload },
@@ -28,6 +46,230 @@ impl<'buf> DigitalGreenCertificate<Decoded<'buf>> {
         Ok((kid, result))
     }
 
+    pub fn verify_signature_with_mode<'a>(
+        &'buf self,
+        keystore: &'a KeyStore,
+        mode: SignatureMode,
+    ) -> Result<(SignatureReport, DigitalGreenCertificate<Verified<'buf>>), DecodeError<'buf>> {
+        self.verify_signature_with_backend(keystore, mode, &DefaultBackend::default())
+    }
+
+    pub fn verify_signature_with_backend<'a, B: CryptoBackend>(
+        &'buf self,
+        keystore: &'a KeyStore,
+        mode: SignatureMode,
+        backend: &B,
+    ) -> Result<(SignatureReport, DigitalGreenCertificate<Verified<'buf>>), DecodeError<'buf>> {
+        let report = match crate::cwt::verify_signature_with_mode(
+            &self.state.cose_msg,
+            keystore,
+            mode,
+            backend,
+        ) {
+            Ok(r) => r,
+
+            Err(e) => return Err(DecodeError::InvalidSignature(e, &self.state.cose_msg)),
+        };
+
+        let hcert_payload = self.verified_payload()?;
+
+        let result = DigitalGreenCertificate {
+            state: Verified { hcert_payload },
+        };
+
+        Ok((report, result))
+    }
+
+    /// Verifies the signature, then rejects the certificate with
+    /// [`DecodeError::Blocklisted`] if its identifier is in `blocklist`.
+    pub fn verify_signature_with_blocklist<'a>(
+        &'buf self,
+        keystore: &'a KeyStore,
+        mode: SignatureMode,
+        blocklist: &Blocklist,
+    ) -> Result<(SignatureReport, DigitalGreenCertificate<Verified<'buf>>), DecodeError<'buf>> {
+        let (report, verified) = self.verify_signature_with_mode(keystore, mode)?;
+
+        let status = verified
+            .hcert_payload()
+            .certificate()
+            .and_then(|cert| cert.identifier_and_country())
+            .map(|(uvci, _)| blocklist.check(uvci))
+            .unwrap_or(BlocklistStatus::NotListed);
+
+        match status {
+            BlocklistStatus::NotListed => Ok((report, verified)),
+            BlocklistStatus::Listed(entry) => Err(DecodeError::Blocklisted(entry)),
+        }
+    }
+
+    /// Verifies the signature against `key` instead of looking it up in a
+    /// keystore.
+    pub fn verify_signature_with_key(
+        &'buf self,
+        key: &PublicKey,
+        mode: SignatureMode,
+    ) -> Result<(ExplicitKeyReport, DigitalGreenCertificate<Verified<'buf>>), DecodeError<'buf>>
+    {
+        let report = match crate::cwt::verify_signature_with_key(
+            &self.state.cose_msg,
+            key,
+            mode,
+            &DefaultBackend::default(),
+        ) {
+            Ok(r) => r,
+
+            Err(e) => return Err(DecodeError::InvalidSignature(e, &self.state.cose_msg)),
+        };
+
+        let hcert_payload = self.verified_payload()?;
+
+        let result = DigitalGreenCertificate {
+            state: Verified { hcert_payload },
+        };
+
+        Ok((report, result))
+    }
+
+    /// Payload of a verified certificate, which must hold an EU DCC.
+    fn verified_payload(&'buf self) -> Result<HCertPayload<'buf>, DecodeError<'buf>> {
+        let hcert_payload: HCertPayload = serde_cbor::from_slice(self.state.cose_msg.payload)?;
+
+        if hcert_payload.certificate().is_none() {
+            return Err(DecodeError::MissingCertificateData);
+        }
+
+        Ok(hcert_payload)
+    }
+
+    /// Key id found in the protected header, base64 encoded.
+    pub fn kid(&self) -> Option<String> {
+        serde_cbor::from_slice::<Generic_Headers>(self.state.cose_msg.protected)
+            .ok()
+            .and_then(|hdr| hdr.kid)
+            .map(base64::encode)
+    }
+
+    pub fn revocation_hashes(&self) -> Result<RevocationHashes, DecodeError<'_>> {
+        let cose_msg = &self.state.cose_msg;
+
+        let protected_hdr: Generic_Headers = serde_cbor::from_slice(cose_msg.protected)?;
+
+        // Only `r` is hashed for ECDSA, which may have been DER encoded. Its
+        // length comes from the header algorithm, which signature
+        // verification checks against the key.
+        let signature = match protected_hdr.alg.and_then(ecdsa::component_len) {
+            Some(component_len) => {
+                ecdsa::signature_r(cose_msg.signature, component_len, SignatureMode::Lenient)
+                    .map_err(|_| {
+                        DecodeError::InvalidSignature(VerificationError::BadSignature, cose_msg)
+                    })?
+            }
+            None => cose_msg.signature.to_vec(),
+        };
+
+        let payload = self.decode_payload()?;
+
+        let (uvci, country) = payload
+            .certificate()
+            .and_then(|cert| cert.identifier_and_country())
+            .ok_or(DecodeError::MissingCertificateData)?;
+
+        Ok(RevocationHashes::compute(uvci, country, &signature))
+    }
+
+    /// Looks the certificate up in the batches of `store` not expired at
+    /// `at`.
+    pub fn check_revocation(
+        &self,
+        store: &RevocationStore,
+        at: DateTime<Utc>,
+    ) -> Result<RevocationStatus, DecodeError<'_>> {
+        let hashes = self.revocation_hashes()?;
+        let kid = self.kid().unwrap_or_default();
+
+        Ok(store.check(&hashes, &kid, at))
+    }
+
+    /// Rejects the certificate with [`DecodeError::Revoked`] if it is in
+    /// the batches of `store` not expired at `at`.
+    pub fn reject_revoked(
+        &self,
+        store: &RevocationStore,
+        at: DateTime<Utc>,
+    ) -> Result<(), DecodeError<'_>> {
+        match self.check_revocation(store, at)? {
+            RevocationStatus::NotRevoked => Ok(()),
+            RevocationStatus::Revoked {
+                batch_id,
+                hash_type,
+            } => Err(DecodeError::Revoked(Revocation::Batch {
+                batch_id,
+                hash_type,
+            })),
+        }
+    }
+
+    /// Rejects the certificate with [`DecodeError::Revoked`] if it is in the
+    /// revocation chunks of `store`, Bloom filters included.
+    pub fn reject_revoked_chunks(&self, store: &ChunkStore) -> Result<(), DecodeError<'_>> {
+        let hashes = self.revocation_hashes()?;
+        let kid = self.kid().unwrap_or_default();
+
+        match store
+            .lookup(&kid, &hashes)
+            .map_err(DecodeError::RevocationCheckFailed)?
+        {
+            ChunkMatch::NotFound => Ok(()),
+            ChunkMatch::Present(hash_type) => {
+                Err(DecodeError::Revoked(Revocation::Chunk(hash_type)))
+            }
+            ChunkMatch::ProbablyPresent {
+                hash_type,
+                false_positive_rate,
+            } => Err(DecodeError::Revoked(Revocation::ProbableChunk {
+                hash_type,
+                false_positive_rate,
+            })),
+        }
+    }
+
+    pub fn check_blocklist(
+        &self,
+        blocklist: &Blocklist,
+    ) -> Result<BlocklistStatus, DecodeError<'_>> {
+        let payload = self.decode_payload()?;
+
+        let (uvci, _) = payload
+            .certificate()
+            .and_then(|cert| cert.identifier_and_country())
+            .ok_or(DecodeError::MissingCertificateData)?;
+
+        Ok(blocklist.check(uvci))
+    }
+
+    /// Validates the certificate against the DCC JSON schema, before it is
+    /// parsed into the typed model.
+    pub fn validate_schema(
+        &self,
+        validator: &SchemaValidator,
+    ) -> Result<Vec<Violation>, DecodeError<'_>> {
+        let payload: Value = serde_cbor::from_slice(self.state.cose_msg.payload)?;
+
+        let dcc = match &payload {
+            Value::Map(claims) => claims
+                .get(&Value::Integer(-260))
+                .and_then(|hcert| match hcert {
+                    Value::Map(hcert) => hcert.get(&Value::Integer(1)),
+                    _ => None,
+                }),
+            _ => None,
+        };
+
+        dcc.map(|dcc| validator.validate_cbor(dcc))
+            .ok_or(DecodeError::MissingCertificateData)
+    }
+
     pub fn payload_len(&self) -> usize {
         self.state.cose_msg.payload.len()
     }
@@ -1,4 +1,8 @@
-use crate::{cose::COSE_Sign1, cwt::VerificationError};
+use crate::{
+    cose::COSE_Sign1,
+    cwt::VerificationError,
+    revocation::{BlocklistEntry, Revocation, RevocationError},
+};
 
 #[derive(Debug)]
 pub enum DecodeError<'c> {
@@ -7,6 +11,16 @@ pub enum DecodeError<'c> {
     DecompressionFailed(std::io::Error),
     InvalidSignature(VerificationError, &'c COSE_Sign1<'c>),
     InvalidText,
+    MissingCertificateData,
+
+    /// The signature is valid, but the certificate is revoked.
+    Revoked(Revocation),
+
+    /// The signature is valid, but the certificate identifier is blocklisted.
+    Blocklisted(BlocklistEntry),
+
+    /// Revocation lists could not be read.
+    RevocationCheckFailed(RevocationError),
     Unknown2DCodeVersion,
 }
 
@@ -1,5 +1,3 @@
-use std::marker::PhantomData;
-
 use crate::{cose::COSE_Sign1, hcert::HCertPayload};
 
 mod decoded;
@@ -9,24 +7,35 @@ mod verified;
 
 pub use error::DecodeError;
 
-pub struct Raw<'r> {
+pub struct Raw {
     buffer: Vec<u8>,
-    __: PhantomData<&'r ()>,
 }
 
 pub struct Decoded<'buf> {
     cose_msg: COSE_Sign1<'buf>,
 }
+/// Only built from payloads holding an EU DCC, see
+/// `DigitalGreenCertificate::<Decoded>::verified_payload`.
 pub struct Verified<'sign1> {
-    pub hcert_payload: HCertPayload<'sign1>,
+    hcert_payload: HCertPayload<'sign1>,
 }
 pub struct Invalid;
 
 pub trait State {}
-impl<'r> State for Raw<'r> {}
+impl State for Raw {}
 impl<'b> State for Decoded<'b> {}
 impl<'s> State for Verified<'s> {}
 
 pub struct DigitalGreenCertificate<T: State> {
     state: T,
 }
+
+/// A verified certificate that does not borrow from the scanned text, see
+/// [`DigitalGreenCertificate::into_owned`].
+pub type OwnedCertificate = DigitalGreenCertificate<Verified<'static>>;
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<OwnedCertificate>();
+};
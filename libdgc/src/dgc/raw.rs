@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, str::FromStr};
+use std::str::FromStr;
 
 use flate2::bufread::ZlibDecoder;
 
@@ -6,8 +6,8 @@ use crate::cose::COSE_Sign1;
 
 use super::{error::DecodeError, Decoded, DigitalGreenCertificate, Raw};
 
-impl<'r> FromStr for DigitalGreenCertificate<Raw<'r>> {
-    type Err = DecodeError<'r>;
+impl FromStr for DigitalGreenCertificate<Raw> {
+    type Err = DecodeError<'static>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         use DecodeError::*;
@@ -33,10 +33,7 @@ impl<'r> FromStr for DigitalGreenCertificate<Raw<'r>> {
                 zlib_decoder.read_to_end(&mut buffer)?;
 
                 Ok(DigitalGreenCertificate {
-                    state: Raw {
-                        buffer,
-                        __: PhantomData,
-                    },
+                    state: Raw { buffer },
                 })
             }
 
@@ -45,7 +42,7 @@ impl<'r> FromStr for DigitalGreenCertificate<Raw<'r>> {
     }
 }
 
-impl DigitalGreenCertificate<Raw<'_>> {
+impl DigitalGreenCertificate<Raw> {
     pub fn decode<'buf>(&'buf self) -> Result<DigitalGreenCertificate<Decoded<'buf>>, DecodeError> {
         let cose_msg: COSE_Sign1 = serde_cbor::from_slice(self.state.buffer.as_slice())?; //Failed to decode signed CWT.
 
@@ -1,6 +1,12 @@
 use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
 
-use crate::hcert::{CertificateData, HCertPayload, Person, Recovery, Test, Vaccine};
+use crate::date::{DateError, PartialDate};
+use crate::extension::Extensions;
+use crate::hcert::{
+    CertificateData, CertificateKind, CertificateKindError, HCertPayload, Person, Recovery, Test,
+    Vaccine,
+};
 
 use super::{DigitalGreenCertificate, Verified};
 
@@ -18,17 +24,34 @@ impl<'sign1> DigitalGreenCertificate<Verified<'sign1>> {
     }
 
     pub fn signature_issuer(&self) -> &str {
-        self.hcert_payload().iss
+        &self.hcert_payload().iss
     }
 
     pub(crate) fn inner(&self) -> &CertificateData {
-        &self.hcert_payload().hcert[&1]
+        self.hcert_payload()
+            .certificate()
+            .expect("Verified certificates are only built with certificate data.")
     }
 
     pub fn person(&self) -> &Person {
         &self.inner().nam
     }
 
+    pub fn date_of_birth(&self) -> Result<PartialDate, DateError> {
+        self.inner().date_of_birth()
+    }
+
+    pub fn kind(&self) -> Result<CertificateKind, CertificateKindError> {
+        self.inner().kind()
+    }
+
+    pub fn kind_with(
+        &self,
+        extensions: &Extensions,
+    ) -> Result<CertificateKind, CertificateKindError> {
+        self.inner().kind_with(extensions)
+    }
+
     pub fn vaccine_data(&self) -> Option<&Vaccine> {
         self.inner().v.as_ref().and_then(|v| v.first())
     }
@@ -40,4 +63,141 @@ impl<'sign1> DigitalGreenCertificate<Verified<'sign1>> {
     pub fn recovery_data(&self) -> Option<&Recovery> {
         self.inner().r.as_ref().and_then(|r| r.first())
     }
+
+    /// The payload as JSON, certificates conforming to the EU DCC schema.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Detaches the certificate from the buffers it was decoded from.
+    pub fn into_owned(self) -> DigitalGreenCertificate<Verified<'static>> {
+        DigitalGreenCertificate {
+            state: Verified {
+                hcert_payload: self.state.hcert_payload.into_owned(),
+            },
+        }
+    }
+}
+
+impl Serialize for DigitalGreenCertificate<Verified<'_>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.hcert_payload().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_cbor::Value;
+    use serde_json::json;
+
+    use super::*;
+    use crate::extension::{ExtensionError, NationalEntry};
+
+    fn dcc() -> serde_json::Value {
+        json!({
+            "ver": "1.3.0",
+            "dob": "1990-01-01",
+            "nam": { "fn": "Dupont", "fnt": "DUPONT", "gnt": "JEAN" },
+            "r": [{
+                "tg": "840539006",
+                "fr": "2021-12-01",
+                "df": "2021-12-12",
+                "du": "2022-05-30",
+                "co": "FR",
+                "is": "Ministère de la Santé",
+                "ci": "URN:UVCI:01:FR:W7V2BE46QSBJ#L"
+            }]
+        })
+    }
+
+    fn payload(dcc: serde_json::Value) -> Vec<u8> {
+        let dcc = serde_cbor::value::to_value(dcc).unwrap();
+        let hcert = Value::Map([(Value::Integer(1), dcc)].into_iter().collect());
+
+        let claims = [
+            (Value::Integer(1), Value::Text("FR".to_owned())),
+            (Value::Integer(4), Value::Integer(1_700_000_000)),
+            (Value::Integer(6), Value::Integer(1_600_000_000)),
+            (Value::Integer(-260), hcert),
+        ];
+
+        serde_cbor::to_vec(&Value::Map(claims.into_iter().collect())).unwrap()
+    }
+
+    fn verified(payload: &[u8]) -> DigitalGreenCertificate<Verified<'_>> {
+        DigitalGreenCertificate {
+            state: Verified {
+                hcert_payload: serde_cbor::from_slice(payload).unwrap(),
+            },
+        }
+    }
+
+    #[test]
+    fn outlives_its_buffer_once_owned() {
+        let buffer = payload(dcc());
+
+        let owned = verified(&buffer).into_owned();
+
+        drop(buffer);
+
+        assert_eq!(owned.signature_issuer(), "FR");
+        assert_eq!(owned.issued_at(), Utc.timestamp(1_600_000_000, 0));
+        assert_eq!(owned.person().fnt, "DUPONT");
+
+        let recovery = owned.recovery_data().unwrap();
+
+        assert_eq!(recovery.ci, "URN:UVCI:01:FR:W7V2BE46QSBJ#L");
+        assert_eq!(recovery.is, "Ministère de la Santé");
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let buffer = payload(dcc());
+
+        let json = verified(&buffer).to_json().unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            json!({
+                "iss": "FR",
+                "iat": 1_600_000_000,
+                "exp": 1_700_000_000,
+                "hcert": { "1": dcc() }
+            })
+        );
+    }
+
+    fn national_entry(_: &Value) -> Result<Box<dyn NationalEntry>, ExtensionError> {
+        Ok(Box::new("national"))
+    }
+
+    #[test]
+    fn tells_the_kind_with_extensions() {
+        let mut national = dcc();
+        national.as_object_mut().unwrap().remove("r");
+        national["nl"] = json!({ "id": 1 });
+
+        let buffer = payload(national);
+        let certificate = verified(&buffer);
+
+        let mut extensions = Extensions::new();
+        extensions.register("nl", national_entry);
+
+        assert_eq!(certificate.kind(), Err(CertificateKindError::NoEntry));
+        assert!(matches!(
+            certificate.kind_with(&extensions),
+            Ok(CertificateKind::National { key: "nl", .. })
+        ));
+
+        let buffer = payload(dcc());
+
+        assert!(matches!(
+            verified(&buffer).kind_with(&extensions),
+            Ok(CertificateKind::Recovery(_))
+        ));
+    }
 }
@@ -2,18 +2,39 @@ use std::fmt::{self, Display};
 
 use crate::{
     dgc::{DigitalGreenCertificate, Verified},
-    hcert::{HCertPayload, Test, Vaccine, Recovery},
+    extension::Extensions,
+    hcert::{CertificateKind, Exemption, HCertPayload, Person, Recovery, Test, Vaccine},
+    valueset::{
+        DeviceStatus, ValueSets, DISEASE_AGENT_TARGETED, TEST_DEVICE, TEST_RESULT, TEST_TYPE,
+        VACCINE_MANUFACTURER, VACCINE_MEDICINAL_PRODUCT, VACCINE_PROPHYLAXIS,
+    },
 };
 
+/// Display name from the value sets, or the one known to the typed code.
+fn display_name<T: Display>(value_sets: &ValueSets, id: &str, code: &str, typed: T) -> String {
+    match value_sets.display(id, code) {
+        Some(name) => name.to_owned(),
+        None => typed.to_string(),
+    }
+}
+
 impl Display for Vaccine<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sets = ValueSets::global();
+
+        let disease = display_name(&sets, DISEASE_AGENT_TARGETED, &self.tg, self.disease());
+        let product = self.medicinal_product();
+        let product = display_name(&sets, VACCINE_MEDICINAL_PRODUCT, &self.mp, product);
+        let vaccine_type = display_name(&sets, VACCINE_PROPHYLAXIS, &self.vp, self.vaccine_type());
+        let manufacturer = display_name(&sets, VACCINE_MANUFACTURER, &self.ma, self.manufacturer());
+
         writeln!(f, "Vaccine data:").ok();
 
-        writeln!(f, "\tTargeted disease: {}", translate_disease(self.tg)).ok();
+        writeln!(f, "\tTargeted disease: {}", disease).ok();
 
-        writeln!(f, "\tName: {}", translate_medicinal_product(self.mp)).ok();
-        writeln!(f, "\tType: {}", translate_vaccine_type(self.vp)).ok();
-        writeln!(f, "\tManufacturer : {}", translate_marketing_org(self.ma)).ok();
+        writeln!(f, "\tName: {}", product).ok();
+        writeln!(f, "\tType: {}", vaccine_type).ok();
+        writeln!(f, "\tManufacturer : {}", manufacturer).ok();
 
         writeln!(f, "\tShot {}/{} done {}.", &self.dn, &self.sd, &self.dt).ok();
 
@@ -23,29 +44,54 @@ impl Display for Vaccine<'_> {
 
 impl Display for Test<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sets = ValueSets::global();
+
+        let disease = display_name(&sets, DISEASE_AGENT_TARGETED, &self.tg, self.disease());
+        let test_type = display_name(&sets, TEST_TYPE, &self.tt, self.test_type());
+        let test_result = display_name(&sets, TEST_RESULT, &self.tr, self.test_result());
+
         writeln!(f, "Test data:").ok();
 
-        writeln!(f, "\tTargeted disease: {}", translate_disease(self.tg)).ok();
+        writeln!(f, "\tTargeted disease: {}", disease).ok();
 
-        writeln!(f, "\tTest type: {}", translate_test_type(self.tt)).ok();
+        writeln!(f, "\tTest type: {}", test_type).ok();
 
-        match (self.nm, self.ma) {
-            (Some(nm), None) => writeln!(f, "\tTest name: {}", nm).ok(),
+        // The schema expects a name for NAATs and a device for RATs, some
+        // issuers set both or neither.
+        if let Some(nm) = &self.nm {
+            writeln!(f, "\tTest name: {}", nm).ok();
+        }
 
-            (None, Some(ma)) =>
-            //TODO: translate that field
-            {
-                writeln!(f, "\tTest device: {}", ma).ok()
+        if let Some(ma) = &self.ma {
+            let device = match sets.rat_devices().get(ma) {
+                Some(device) => device.to_string(),
+                None => sets.display(TEST_DEVICE, ma).unwrap_or(ma).to_owned(),
+            };
+
+            writeln!(f, "\tTest device: {}", device).ok();
+
+            match self.device_status(sets.rat_devices()) {
+                Ok(Some(DeviceStatus::Approved)) => {
+                    writeln!(f, "\tDevice was on the EU common list at sample time.").ok();
+                }
+                Ok(Some(DeviceStatus::NotApproved)) => {
+                    writeln!(f, "\tDevice was NOT on the EU common list at sample time.").ok();
+                }
+                _ => {}
             }
+        }
 
-            invalid => panic!("Invalid combination: {:?}", invalid),
-        };
+        if self.nm.is_none() && self.ma.is_none() {
+            writeln!(f, "\tTest name: unknown").ok();
+        }
 
         writeln!(f, "\tSample collection date: {}", self.sc).ok();
 
-        writeln!(f, "\tTest result: {}", translate_test_result(self.tr)).ok();
+        writeln!(f, "\tTest result: {}", test_result).ok();
 
-        writeln!(f, "\tTest facility: {}", self.tc).ok();
+        if let Some(tc) = &self.tc {
+            writeln!(f, "\tTest facility: {}", tc).ok();
+        }
 
         writeln!(f, "\tTest id: {}", self.ci).ok();
 
@@ -55,9 +101,13 @@ impl Display for Test<'_> {
 
 impl Display for Recovery<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sets = ValueSets::global();
+
+        let disease = display_name(&sets, DISEASE_AGENT_TARGETED, &self.tg, self.disease());
+
         writeln!(f, "Recovery data:").ok();
 
-        writeln!(f, "\tTargeted disease: {}", translate_disease(self.tg)).ok();
+        writeln!(f, "\tTargeted disease: {}", disease).ok();
 
         writeln!(f, "\tDate of first NAAT positive test: {}", self.fr).ok();
 
@@ -71,28 +121,79 @@ impl Display for Recovery<'_> {
     }
 }
 
+impl Display for Exemption<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sets = ValueSets::global();
+
+        let disease = display_name(&sets, DISEASE_AGENT_TARGETED, &self.tg, self.disease());
+
+        writeln!(f, "Vaccination exemption data:").ok();
+
+        writeln!(f, "\tTargeted disease: {}", disease).ok();
+
+        writeln!(f, "\tExemption valid from: {}", self.df).ok();
+
+        writeln!(f, "\tExemption valid until: {}", self.du).ok();
+
+        writeln!(f, "\tCertificate id: {}", self.ci).ok();
+
+        writeln!(f, "Certificate issued by {} ({}):", &self.is, &self.co)
+    }
+}
+
+/// Surname then forename, falling back to their standardized forms when
+/// only those are present.
+impl Display for Person<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = match (&self.sn, &self.gn) {
+            (None, None) => [Some(&self.fnt), self.gnt.as_ref()]
+                .into_iter()
+                .flatten()
+                .map(|name| name.as_ref())
+                .collect(),
+
+            (sn, gn) => [sn.as_ref(), gn.as_ref()]
+                .into_iter()
+                .flatten()
+                .map(|name| name.as_ref())
+                .collect(),
+        };
+
+        f.write_str(&names.join(" "))
+    }
+}
+
 impl Display for HCertPayload<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let cert = &self.hcert[&1];
+        let cert = match self.certificate() {
+            Some(cert) => cert,
+            None => return writeln!(f, "Health certificate without EU DCC data."),
+        };
         let person = &cert.nam;
         let dob = &cert.dob;
 
         writeln!(
             f,
-            "Digital Green Certificate for {} {} (born {}):",
-            person.sn, person.gn, dob
+            "Digital Green Certificate for {} (born {}):",
+            person, dob
         )
         .ok();
 
         let days = self.expiring_at() - self.issued_at();
 
-        if let Some([vaccine_data]) = &cert.v {
-            vaccine_data.fmt(f).ok();
-        } else if let Some([test_data]) = &cert.t {
-            test_data.fmt(f).ok();
-        } else if let Some([recovery_data]) = &cert.r {
-            recovery_data.fmt(f).ok();
-        }
+        let extensions = Extensions::global();
+
+        match cert.kind_with(&extensions) {
+            Ok(CertificateKind::Vaccination(vaccine_data)) => vaccine_data.fmt(f).ok(),
+            Ok(CertificateKind::Test(test_data)) => test_data.fmt(f).ok(),
+            Ok(CertificateKind::Recovery(recovery_data)) => recovery_data.fmt(f).ok(),
+            Ok(CertificateKind::Exemption(exemption_data)) => exemption_data.fmt(f).ok(),
+            Ok(CertificateKind::National { key, value }) => match extensions.parse(key, value) {
+                Ok(entry) => writeln!(f, "National data ({}):\n{}", key, entry).ok(),
+                Err(e) => writeln!(f, "\tInvalid national data ({}): {:?}", key, e).ok(),
+            },
+            Err(e) => writeln!(f, "\tInvalid certificate: {:?}", e).ok(),
+        };
 
         writeln!(
             f,
@@ -113,75 +214,42 @@ impl Display for DigitalGreenCertificate<Verified<'_>> {
     }
 }
 
-pub fn translate_disease(tg: &str) -> &str {
-    match tg {
-        "840539006" => "COVID-19",
-
-        _ => tg,
-    }
-}
-
-pub fn translate_vaccine_type(vp: &str) -> &str {
-    match vp {
-        "1119305005" => "SARS-CoV2 antigen vaccine",
-        "1119349007" => "SARS-CoV2 mRNA vaccine",
-        "J07BX03" => "covid-19 vaccines",
-        _ => vp,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test(nm: Option<&'static str>, ma: Option<&'static str>) -> String {
+        let json = serde_json::json!({
+            "tg": "840539006",
+            "tt": "LP6464-4",
+            "nm": nm,
+            "ma": ma,
+            "sc": "2021-05-30T10:12:22Z",
+            "tr": "260415000",
+            "tc": "Testzentrum",
+            "co": "DE",
+            "is": "Robert Koch-Institut",
+            "ci": "URN:UVCI:01:DE:187/37512422923"
+        })
+        .to_string();
+
+        let test: Test = serde_json::from_str(&json).unwrap();
+
+        test.to_string()
     }
-}
 
-pub fn translate_medicinal_product(mp: &str) -> &str {
-    match mp {
-        "EU/1/20/1528" => "Comirnaty",
-        "EU/1/20/1507" => "COVID-19 Vaccine Moderna",
-        "EU/1/21/1529" => "Vaxzevria",
-        "EU/1/20/1525" => "COVID-19 Vaccine Janssen",
+    #[test]
+    fn displays_any_combination_of_name_and_device() {
+        let naat = test(Some("Roche LightCycler qPCR"), None);
 
-        "Covaxin" => "Covaxin (also known as BBV152 A, B, C)",
-        "Inactivated-SARS-CoV-2-Vero-Cell" => "Inactivated SARS-CoV-2 (Vero Cell)",
+        assert!(naat.contains("Test name: Roche LightCycler qPCR"));
+        assert!(!naat.contains("Test device"));
 
-        "CVnCoV" | "NVX-CoV2373" | "Sputnik-V" | "Convidecia" | "EpiVacCorona" | "BBIBP-CorV" | "CoronaVac" => mp,
-
-        _ => mp,
-    }
-}
-
-pub fn translate_marketing_org(ma: &str) -> &str {
-    match ma {
-        "ORG-100001699" => "AstraZeneca AB",
-        "ORG-100030215" => "Biontech Manufacturing GmbH",
-        "ORG-100001417" => "Janssen-Cilag International",
-        "ORG-100031184" => "Moderna Biotech Spain S.L.",
-        "ORG-100006270" => "Curevac AG",
-        "ORG-100013793" => "CanSino Biologics",
-        "ORG-100020693" => "China Sinopharm International Corp. - Beijing location",
-        "ORG-100010771" => "Sinopharm Weiqida Europe Pharmaceutical s.r.o. - Prague location",
-        "ORG-100024420" => {
-            "Sinopharm Zhijun (Shenzhen) Pharmaceutical Co. Ltd. - Shenzhen location"
-        }
-        "ORG-100032020" => "Novavax CZ AS",
-        "Gamaleya-Research-Institute" => "Gamaleya Research Institute",
-        "Vector-Institute" => "Vector Institute",
-        "Sinovac-Biotech" => "Sinovac Biotech",
-        "Bharat-Biotech" => "Bharat Biotech",
+        let both = test(Some("Roche LightCycler qPCR"), Some("1232"));
 
-        _ => ma,
-    }
-}
-
-pub fn translate_test_type(tt: &str) -> &str {
-    match tt {
-        "LP6464-4" => "Nucleic acid amplification with probe detection",
-        "LP217198-3" => "Rapid immunoassay",
-
-        _ => tt,
-    }
-}
+        assert!(both.contains("Test name: Roche LightCycler qPCR"));
+        assert!(both.contains("Test device: "));
 
-pub fn translate_test_result(tr: &str) -> &str {
-    match tr {
-        "260415000" => "Not detected",
-        "260373001" => "Detected",
-        _ => tr,
+        assert!(test(None, None).contains("Test name: unknown"));
     }
 }
@@ -0,0 +1,89 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde_cbor::Value;
+
+static GLOBAL: Lazy<RwLock<Arc<Extensions>>> = Lazy::new(Default::default);
+
+#[derive(Debug)]
+pub enum ExtensionError {
+    /// No parser is registered for this key
+    UnknownExtension(String),
+    InvalidEntry(String),
+}
+
+/// An entry of a national certificate type, parsed by the parser registered
+/// for its key.
+pub trait NationalEntry: fmt::Display + fmt::Debug + Send + Sync {}
+
+impl<T: fmt::Display + fmt::Debug + Send + Sync> NationalEntry for T {}
+
+pub type ExtensionParser = fn(&Value) -> Result<Box<dyn NationalEntry>, ExtensionError>;
+
+/// Parsers for the national entries some countries put in certificates in
+/// place of `v`, `t`, `r` or `e`, by key.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    parsers: HashMap<String, ExtensionParser>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `parser` for the entries found under `key`, replacing any
+    /// previous one.
+    pub fn register(&mut self, key: &str, parser: ExtensionParser) {
+        log::debug!(target:"dgc", "Registered parser for national entry '{}'", key);
+
+        self.parsers.insert(key.to_owned(), parser);
+    }
+
+    pub fn is_registered(&self, key: &str) -> bool {
+        self.parsers.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.parsers.keys().map(String::as_str)
+    }
+
+    pub fn parse(
+        &self,
+        key: &str,
+        value: &Value,
+    ) -> Result<Box<dyn NationalEntry>, ExtensionError> {
+        match self.parsers.get(key) {
+            Some(parser) => parser(value),
+            None => Err(ExtensionError::UnknownExtension(key.to_owned())),
+        }
+    }
+
+    /// Extensions used when displaying certificates, none unless replaced
+    /// with [`Extensions::install`].
+    pub fn global() -> Arc<Extensions> {
+        GLOBAL.read().unwrap().clone()
+    }
+
+    pub fn install(self) {
+        *GLOBAL.write().unwrap() = Arc::new(self);
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.parsers.keys()).finish()
+    }
+}
+
+/// Deserializes an entry into `T`, for parsers of entries with a fixed
+/// structure.
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T, ExtensionError> {
+    serde_cbor::value::from_value(value.clone())
+        .map_err(|e| ExtensionError::InvalidEntry(e.to_string()))
+}
@@ -1,15 +1,35 @@
-use std::{collections::HashMap, fmt, marker::PhantomData};
+use std::{borrow::Cow, collections::BTreeMap, fmt, marker::PhantomData};
 
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
 use serde::{
     de::{self, Deserializer, MapAccess, Visitor},
-    Deserialize,
+    ser::{self, SerializeMap, Serializer},
+    Deserialize, Serialize,
+};
+use serde_bytes::Bytes;
+use serde_cbor::Value;
+
+use crate::{
+    date::{self, DateError, PartialDate},
+    extension::Extensions,
+    icao,
+    uvci::{Uvci, UvciError},
+    valueset::{
+        DeviceStatus, Disease, Manufacturer, MedicinalProduct, RatDevices, TestResult, TestType,
+        VaccineType,
+    },
 };
 
 #[derive(Debug, PartialEq)]
 pub struct HCertPayload<'cose> {
     /// Issuer
-    pub iss: &'cose str,
+    pub iss: Cow<'cose, str>,
+
+    /// Subject
+    pub sub: Option<Cow<'cose, str>>,
+
+    /// Audience
+    pub aud: Option<Cow<'cose, str>>,
 
     /// Issuing Date
     pub iat: u32,
@@ -17,11 +37,38 @@ pub struct HCertPayload<'cose> {
     /// Expiring Date
     pub exp: u32,
 
+    /// Not Before
+    pub nbf: Option<u32>,
+
+    /// CWT ID
+    pub cti: Option<Cow<'cose, [u8]>>,
+
     /// Payload
-    pub hcert: HashMap<u8, CertificateData<'cose>>,
+    pub hcert: HCert<'cose>,
+
+    /// Claims not registered in RFC 8392, e.g. national ones
+    pub unknown_claims: BTreeMap<Value, Value>,
 }
 
-impl HCertPayload<'_> {
+impl<'cose> HCertPayload<'cose> {
+    /// The EU DCC found under the key `1` of the `-260` claim.
+    pub fn certificate(&self) -> Option<&CertificateData<'cose>> {
+        self.hcert.dcc.as_ref()
+    }
+
+    pub fn kind(&self) -> Result<CertificateKind<'_, 'cose>, CertificateKindError> {
+        self.kind_with(&Extensions::new())
+    }
+
+    pub fn kind_with(
+        &self,
+        extensions: &Extensions,
+    ) -> Result<CertificateKind<'_, 'cose>, CertificateKindError> {
+        self.certificate()
+            .ok_or(CertificateKindError::MissingCertificateData)?
+            .kind_with(extensions)
+    }
+
     pub fn issued_at(&self) -> DateTime<Utc> {
         Utc.timestamp(self.iat as i64, 0)
     }
@@ -29,53 +76,255 @@ impl HCertPayload<'_> {
     pub fn expiring_at(&self) -> DateTime<Utc> {
         Utc.timestamp(self.exp as i64, 0)
     }
+
+    pub fn not_before(&self) -> Option<DateTime<Utc>> {
+        self.nbf.map(|nbf| Utc.timestamp(nbf as i64, 0))
+    }
+
+    /// Copies every borrowed field, so that the payload outlives the COSE
+    /// buffer it was decoded from.
+    pub fn into_owned(self) -> HCertPayload<'static> {
+        HCertPayload {
+            iss: owned(self.iss),
+            sub: self.sub.map(owned),
+            aud: self.aud.map(owned),
+            iat: self.iat,
+            exp: self.exp,
+            nbf: self.nbf,
+            cti: self.cti.map(|cti| Cow::Owned(cti.into_owned())),
+            hcert: self.hcert.into_owned(),
+            unknown_claims: self.unknown_claims,
+        }
+    }
+}
+
+fn owned(cow: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(cow.into_owned())
 }
 
-#[derive(Debug, PartialEq, Deserialize)]
+/// The single vaccination, test or recovery entry of a certificate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CertificateKind<'a, 'hcert> {
+    Vaccination(&'a Vaccine<'hcert>),
+    Test(&'a Test<'hcert>),
+    Recovery(&'a Recovery<'hcert>),
+    Exemption(&'a Exemption<'hcert>),
+
+    /// An entry for which a parser is registered in the extensions given to
+    /// [`CertificateData::kind_with`]
+    National {
+        key: &'a str,
+        value: &'a Value,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateKindError {
+    MissingCertificateData,
+    /// None of `v`, `t`, `r`, `e` or a national entry is present
+    NoEntry,
+    /// More than one of `v`, `t`, `r`, `e` and national entries are present
+    SeveralEntries,
+}
+
+/// Content of the `-260` claim, the health certificates of the payload.
+#[derive(Debug, Default, PartialEq)]
+pub struct HCert<'cose> {
+    /// EU Digital COVID Certificate, found under the key `1`
+    pub dcc: Option<CertificateData<'cose>>,
+
+    /// Health certificates found under other keys, which some schemes use
+    /// for their own certificate types
+    pub others: BTreeMap<Value, Value>,
+}
+
+impl HCert<'_> {
+    pub fn into_owned(self) -> HCert<'static> {
+        HCert {
+            dcc: self.dcc.map(CertificateData::into_owned),
+            others: self.others,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct CertificateData<'hcert> {
     /// Date Of Birth
-    pub dob: &'hcert str,
+    #[serde(borrow)]
+    pub dob: Cow<'hcert, str>,
 
     /// Version
-    pub ver: &'hcert str,
+    #[serde(borrow)]
+    pub ver: Cow<'hcert, str>,
 
     /// Forename & Surname
+    #[serde(borrow)]
     pub nam: Person<'hcert>,
 
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
     pub v: Option<[Vaccine<'hcert>; 1]>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
     pub t: Option<[Test<'hcert>; 1]>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
     pub r: Option<[Recovery<'hcert>; 1]>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub e: Option<[Exemption<'hcert>; 1]>,
+
+    /// Fields outside of the EU schema, such as national entries
+    #[serde(flatten, serialize_with = "serialize_unknown_fields")]
+    pub unknown_fields: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, PartialEq, Deserialize)]
+impl<'hcert> CertificateData<'hcert> {
+    /// The entry of the certificate, the schema requiring exactly one of
+    /// `v`, `t` and `r`. Exemptions are counted as well, but national entries
+    /// are not: see [`CertificateData::kind_with`].
+    pub fn kind(&self) -> Result<CertificateKind<'_, 'hcert>, CertificateKindError> {
+        self.kind_with(&Extensions::new())
+    }
+
+    /// Like [`CertificateData::kind`], also counting the national entries
+    /// for which a parser is registered in `extensions`.
+    pub fn kind_with(
+        &self,
+        extensions: &Extensions,
+    ) -> Result<CertificateKind<'_, 'hcert>, CertificateKindError> {
+        let mut entries = self
+            .v
+            .iter()
+            .map(|[v]| CertificateKind::Vaccination(v))
+            .chain(self.t.iter().map(|[t]| CertificateKind::Test(t)))
+            .chain(self.r.iter().map(|[r]| CertificateKind::Recovery(r)))
+            .chain(self.e.iter().map(|[e]| CertificateKind::Exemption(e)))
+            .chain(self.national_entries(extensions));
+
+        match (entries.next(), entries.next()) {
+            (Some(kind), None) => Ok(kind),
+            (None, _) => Err(CertificateKindError::NoEntry),
+            (Some(_), Some(_)) => Err(CertificateKindError::SeveralEntries),
+        }
+    }
+
+    /// Unknown fields for which a parser is registered in `extensions`.
+    pub fn national_entries(&self, extensions: &Extensions) -> Vec<CertificateKind<'_, 'hcert>> {
+        self.unknown_fields
+            .iter()
+            .filter(|(key, _)| extensions.is_registered(key))
+            .map(|(key, value)| CertificateKind::National { key, value })
+            .collect()
+    }
+
+    /// Certificate identifier and country of the vaccination, test or
+    /// recovery entry.
+    pub fn identifier_and_country(&self) -> Option<(&str, &str)> {
+        match self.kind().ok()? {
+            CertificateKind::Vaccination(v) => Some((&v.ci, &v.co)),
+            CertificateKind::Test(t) => Some((&t.ci, &t.co)),
+            CertificateKind::Recovery(r) => Some((&r.ci, &r.co)),
+            CertificateKind::Exemption(e) => Some((&e.ci, &e.co)),
+            CertificateKind::National { .. } => None,
+        }
+    }
+
+    pub fn date_of_birth(&self) -> Result<PartialDate, DateError> {
+        date::parse_partial_date("dob", &self.dob)
+    }
+
+    /// Every date or datetime field that could not be parsed.
+    pub fn invalid_dates(&self) -> Vec<DateError> {
+        let mut errors = vec![];
+
+        errors.extend(self.date_of_birth().err());
+
+        if let Some([v]) = &self.v {
+            errors.extend(v.vaccination_date().err());
+        }
+
+        if let Some([t]) = &self.t {
+            errors.extend(t.sample_collected_at().err());
+        }
+
+        if let Some([r]) = &self.r {
+            errors.extend(r.first_positive_test_date().err());
+            errors.extend(r.valid_from().err());
+            errors.extend(r.valid_until().err());
+        }
+
+        if let Some([e]) = &self.e {
+            errors.extend(e.valid_from().err());
+            errors.extend(e.valid_until().err());
+        }
+
+        errors
+    }
+
+    pub fn into_owned(self) -> CertificateData<'static> {
+        CertificateData {
+            dob: owned(self.dob),
+            ver: owned(self.ver),
+            nam: self.nam.into_owned(),
+            v: self.v.map(|[v]| [v.into_owned()]),
+            t: self.t.map(|[t]| [t.into_owned()]),
+            r: self.r.map(|[r]| [r.into_owned()]),
+            e: self.e.map(|[e]| [e.into_owned()]),
+            unknown_fields: self.unknown_fields,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct Person<'cert> {
     /// Surname
-    #[serde(rename = "fn")]
-    pub sn: &'cert str,
+    #[serde(rename = "fn", borrow, skip_serializing_if = "Option::is_none")]
+    pub sn: Option<Cow<'cert, str>>,
 
     /// Standardized Surname
-    pub fnt: &'cert str,
+    #[serde(borrow)]
+    pub fnt: Cow<'cert, str>,
 
     /// Forename
-    pub gn: &'cert str,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub gn: Option<Cow<'cert, str>>,
 
     /// Standardized Forename
-    pub gnt: &'cert str,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub gnt: Option<Cow<'cert, str>>,
+}
+
+impl Person<'_> {
+    pub fn into_owned(self) -> Person<'static> {
+        Person {
+            sn: self.sn.map(owned),
+            fnt: owned(self.fnt),
+            gn: self.gn.map(owned),
+            gnt: self.gnt.map(owned),
+        }
+    }
+
+    /// Standardized names as written in the MRZ of a travel document, e.g.
+    /// `DOE<<JOHN`, see [`icao::mrz_field`] to fit it in a given length.
+    pub fn mrz_name(&self) -> String {
+        icao::mrz_name(&self.fnt, self.gnt.as_deref())
+    }
 }
 
-#[derive(Debug, PartialEq, Deserialize)]
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct Vaccine<'cert> {
     /// Targeted agent or disease
-    pub tg: &'cert str,
+    #[serde(borrow)]
+    pub tg: Cow<'cert, str>,
 
     /// type of Vaccine or Prophylaxis
-    pub vp: &'cert str,
+    #[serde(borrow)]
+    pub vp: Cow<'cert, str>,
 
     /// Medicinal Product
-    pub mp: &'cert str,
+    #[serde(borrow)]
+    pub mp: Cow<'cert, str>,
 
     /// Marketing Authorization holder
-    pub ma: &'cert str,
+    #[serde(borrow)]
+    pub ma: Cow<'cert, str>,
 
     /// Dose Number
     pub dn: u8,
@@ -84,88 +333,292 @@ pub struct Vaccine<'cert> {
     pub sd: u8,
 
     /// Date of vaccination
-    pub dt: &'cert str,
+    #[serde(borrow)]
+    pub dt: Cow<'cert, str>,
 
     /// Country
-    pub co: &'cert str,
+    #[serde(borrow)]
+    pub co: Cow<'cert, str>,
 
     /// Certificate Issuer
-    pub is: &'cert str,
+    #[serde(borrow)]
+    pub is: Cow<'cert, str>,
 
     /// Certificate Identifier
-    pub ci: &'cert str,
+    #[serde(borrow)]
+    pub ci: Cow<'cert, str>,
 }
 
-#[derive(Debug, PartialEq, Deserialize)]
+impl Vaccine<'_> {
+    pub fn disease(&self) -> Disease {
+        Disease::from_code(&self.tg)
+    }
+
+    pub fn vaccine_type(&self) -> VaccineType {
+        VaccineType::from_code(&self.vp)
+    }
+
+    pub fn medicinal_product(&self) -> MedicinalProduct {
+        MedicinalProduct::from_code(&self.mp)
+    }
+
+    pub fn manufacturer(&self) -> Manufacturer {
+        Manufacturer::from_code(&self.ma)
+    }
+
+    pub fn uvci(&self) -> Result<Uvci, UvciError> {
+        self.ci.parse()
+    }
+
+    pub fn vaccination_date(&self) -> Result<NaiveDate, DateError> {
+        date::parse_date("dt", &self.dt)
+    }
+
+    pub fn into_owned(self) -> Vaccine<'static> {
+        Vaccine {
+            tg: owned(self.tg),
+            vp: owned(self.vp),
+            mp: owned(self.mp),
+            ma: owned(self.ma),
+            dn: self.dn,
+            sd: self.sd,
+            dt: owned(self.dt),
+            co: owned(self.co),
+            is: owned(self.is),
+            ci: owned(self.ci),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct Test<'cert> {
     /// Targeted agent or disease
-    pub tg: &'cert str,
+    #[serde(borrow)]
+    pub tg: Cow<'cert, str>,
 
     ///The type of test
-    pub tt: &'cert str,
+    #[serde(borrow)]
+    pub tt: Cow<'cert, str>,
 
     //Test name (nucleic acid amplification tests only)
-    pub nm: Option<&'cert str>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub nm: Option<Cow<'cert, str>>,
 
     //Test device identifier(rapid antigen tests only)
-    pub ma: Option<&'cert str>,
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub ma: Option<Cow<'cert, str>>,
 
     //Date and time of the test sample collection
-    ///TODO: parse as DateTime<Utc> ?
-    pub sc: &'cert str,
+    #[serde(borrow)]
+    pub sc: Cow<'cert, str>,
 
     ///Result of the test
-    pub tr: &'cert str,
+    #[serde(borrow)]
+    pub tr: Cow<'cert, str>,
 
-    ///Testing centre or facility
-    pub tc: &'cert str,
+    ///Testing centre or facility, optional since schema 1.3.0
+    #[serde(borrow, skip_serializing_if = "Option::is_none")]
+    pub tc: Option<Cow<'cert, str>>,
 
     ///Member State or third country in which the test was carried out
-    pub co: &'cert str,
+    #[serde(borrow)]
+    pub co: Cow<'cert, str>,
 
     /// Certificate Issuer
-    pub is: &'cert str,
+    #[serde(borrow)]
+    pub is: Cow<'cert, str>,
 
     /// Certificate Identifier
-    pub ci: &'cert str,
+    #[serde(borrow)]
+    pub ci: Cow<'cert, str>,
 }
 
-#[derive(Debug, PartialEq, Deserialize)]
-pub struct Recovery<'cert> {
+impl Test<'_> {
+    pub fn disease(&self) -> Disease {
+        Disease::from_code(&self.tg)
+    }
+
+    pub fn test_type(&self) -> TestType {
+        TestType::from_code(&self.tt)
+    }
+
+    pub fn test_result(&self) -> TestResult {
+        TestResult::from_code(&self.tr)
+    }
+
+    pub fn uvci(&self) -> Result<Uvci, UvciError> {
+        self.ci.parse()
+    }
+
+    pub fn sample_collected_at(&self) -> Result<DateTime<FixedOffset>, DateError> {
+        date::parse_datetime("sc", &self.sc)
+    }
+
+    /// Whether the rapid antigen test device was on the EU common list when
+    /// the sample was collected, `None` for tests without a device id.
+    pub fn device_status(&self, devices: &RatDevices) -> Result<Option<DeviceStatus>, DateError> {
+        let ma = match &self.ma {
+            Some(ma) => ma,
+            None => return Ok(None),
+        };
+
+        let collected_at = self.sample_collected_at()?.with_timezone(&Utc);
 
+        Ok(Some(devices.status_at(ma, collected_at)))
+    }
+
+    pub fn into_owned(self) -> Test<'static> {
+        Test {
+            tg: owned(self.tg),
+            tt: owned(self.tt),
+            nm: self.nm.map(owned),
+            ma: self.ma.map(owned),
+            sc: owned(self.sc),
+            tr: owned(self.tr),
+            tc: self.tc.map(owned),
+            co: owned(self.co),
+            is: owned(self.is),
+            ci: owned(self.ci),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Recovery<'cert> {
     /// Disease or agent from which the holder has recovered
-    pub tg: &'cert str,
+    #[serde(borrow)]
+    pub tg: Cow<'cert, str>,
 
     /// Date of the holder’s first positive NAAT test result
-    /// TODO: parse date ?
-    pub fr: &'cert str,
+    #[serde(borrow)]
+    pub fr: Cow<'cert, str>,
 
     ///Member State or third country in which the test was carried out
-    pub co: &'cert str,
+    #[serde(borrow)]
+    pub co: Cow<'cert, str>,
 
     /// Certificate Issuer
-    pub is: &'cert str,
+    #[serde(borrow)]
+    pub is: Cow<'cert, str>,
 
     /// Certificate valid from
-    /// TODO: parse date ?
-    pub df: &'cert str,
+    #[serde(borrow)]
+    pub df: Cow<'cert, str>,
+
+    /// Certificate valid until
+    #[serde(borrow)]
+    pub du: Cow<'cert, str>,
+
+    /// Certificate Identifier
+    #[serde(borrow)]
+    pub ci: Cow<'cert, str>,
+}
+
+impl Recovery<'_> {
+    pub fn disease(&self) -> Disease {
+        Disease::from_code(&self.tg)
+    }
 
-    /// Certificate valid until 
-    /// TODO: parse date ?
-    pub du: &'cert str,
+    pub fn uvci(&self) -> Result<Uvci, UvciError> {
+        self.ci.parse()
+    }
+
+    pub fn first_positive_test_date(&self) -> Result<NaiveDate, DateError> {
+        date::parse_date("fr", &self.fr)
+    }
+
+    pub fn valid_from(&self) -> Result<NaiveDate, DateError> {
+        date::parse_date("df", &self.df)
+    }
+
+    pub fn valid_until(&self) -> Result<NaiveDate, DateError> {
+        date::parse_date("du", &self.du)
+    }
+
+    pub fn into_owned(self) -> Recovery<'static> {
+        Recovery {
+            tg: owned(self.tg),
+            fr: owned(self.fr),
+            co: owned(self.co),
+            is: owned(self.is),
+            df: owned(self.df),
+            du: owned(self.du),
+            ci: owned(self.ci),
+        }
+    }
+}
+
+/// Medical exemption from vaccination, issued by some countries in place
+/// of a vaccination entry.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Exemption<'cert> {
+    /// Disease or agent the holder is exempted from being vaccinated against
+    #[serde(borrow)]
+    pub tg: Cow<'cert, str>,
+
+    /// Member State or third country which issued the exemption
+    #[serde(borrow)]
+    pub co: Cow<'cert, str>,
+
+    /// Certificate Issuer
+    #[serde(borrow)]
+    pub is: Cow<'cert, str>,
+
+    /// Exemption valid from
+    #[serde(borrow)]
+    pub df: Cow<'cert, str>,
+
+    /// Exemption valid until
+    #[serde(borrow)]
+    pub du: Cow<'cert, str>,
 
     /// Certificate Identifier
-    pub ci: &'cert str,
+    #[serde(borrow)]
+    pub ci: Cow<'cert, str>,
+}
+
+impl Exemption<'_> {
+    pub fn disease(&self) -> Disease {
+        Disease::from_code(&self.tg)
+    }
+
+    pub fn uvci(&self) -> Result<Uvci, UvciError> {
+        self.ci.parse()
+    }
+
+    pub fn valid_from(&self) -> Result<NaiveDate, DateError> {
+        date::parse_date("df", &self.df)
+    }
+
+    pub fn valid_until(&self) -> Result<NaiveDate, DateError> {
+        date::parse_date("du", &self.du)
+    }
+
+    pub fn into_owned(self) -> Exemption<'static> {
+        Exemption {
+            tg: owned(self.tg),
+            co: owned(self.co),
+            is: owned(self.is),
+            df: owned(self.df),
+            du: owned(self.du),
+            ci: owned(self.ci),
+        }
+    }
 }
 
-const FIELDS: &[&str] = &["iss", "iat", "exp", "hcert"];
+const FIELDS: &[&str] = &["iss", "sub", "aud", "exp", "nbf", "iat", "cti", "hcert"];
 
 #[allow(clippy::upper_case_acronyms)]
 enum Field {
     ISS,
-    IAT,
+    SUB,
+    AUD,
     EXP,
+    NBF,
+    IAT,
+    CTI,
     HCERT,
+    Unknown(Value),
 }
 impl<'de> de::Deserialize<'de> for Field {
     fn deserialize<D>(deserializer: D) -> Result<Field, D::Error>
@@ -181,7 +634,7 @@ impl<'de> Visitor<'de> for FieldVisitor {
     type Value = Field;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("stuff")
+        formatter.write_str("a CWT claim key")
     }
 
     fn visit_u64<E>(self, value: u64) -> Result<Field, E>
@@ -190,9 +643,13 @@ impl<'de> Visitor<'de> for FieldVisitor {
     {
         match value {
             1 => Ok(Field::ISS),
-            6 => Ok(Field::IAT),
+            2 => Ok(Field::SUB),
+            3 => Ok(Field::AUD),
             4 => Ok(Field::EXP),
-            _ => Err(de::Error::unknown_field(&format!("{}", value), FIELDS)),
+            5 => Ok(Field::NBF),
+            6 => Ok(Field::IAT),
+            7 => Ok(Field::CTI),
+            _ => Ok(Field::Unknown(Value::Integer(value.into()))),
         }
     }
 
@@ -202,9 +659,23 @@ impl<'de> Visitor<'de> for FieldVisitor {
     {
         match value {
             -260 => Ok(Field::HCERT),
-            _ => Err(de::Error::unknown_field(&format!("{}", value), FIELDS)),
+            _ => Ok(Field::Unknown(Value::Integer(value.into()))),
         }
     }
+
+    fn visit_str<E>(self, value: &str) -> Result<Field, E>
+    where
+        E: de::Error,
+    {
+        Ok(Field::Unknown(Value::Text(value.to_owned())))
+    }
+
+    fn visit_bytes<E>(self, value: &[u8]) -> Result<Field, E>
+    where
+        E: de::Error,
+    {
+        Ok(Field::Unknown(Value::Bytes(value.to_vec())))
+    }
 }
 
 struct HCertPayloadVisitor<'v> {
@@ -224,8 +695,13 @@ impl<'cose, 'de: 'cose> Visitor<'de> for HCertPayloadVisitor<'cose> {
     {
         let mut iat = None;
         let mut iss = None;
+        let mut sub = None;
+        let mut aud = None;
         let mut exp = None;
+        let mut nbf = None;
+        let mut cti = None;
         let mut hcert = None;
+        let mut unknown_claims = BTreeMap::new();
 
         while let Some(key) = map.next_key()? {
             match key {
@@ -240,7 +716,21 @@ impl<'cose, 'de: 'cose> Visitor<'de> for HCertPayloadVisitor<'cose> {
                     if iss.is_some() {
                         return Err(de::Error::duplicate_field("iss"));
                     }
-                    iss = Some(map.next_value()?);
+                    iss = Some(Cow::Borrowed(map.next_value::<&'de str>()?));
+                }
+
+                Field::SUB => {
+                    if sub.is_some() {
+                        return Err(de::Error::duplicate_field("sub"));
+                    }
+                    sub = Some(Cow::Borrowed(map.next_value::<&'de str>()?));
+                }
+
+                Field::AUD => {
+                    if aud.is_some() {
+                        return Err(de::Error::duplicate_field("aud"));
+                    }
+                    aud = Some(Cow::Borrowed(map.next_value::<&'de str>()?));
                 }
 
                 Field::EXP => {
@@ -250,12 +740,37 @@ impl<'cose, 'de: 'cose> Visitor<'de> for HCertPayloadVisitor<'cose> {
                     exp = Some(map.next_value()?);
                 }
 
+                Field::NBF => {
+                    if nbf.is_some() {
+                        return Err(de::Error::duplicate_field("nbf"));
+                    }
+                    nbf = Some(map.next_value()?);
+                }
+
+                Field::CTI => {
+                    if cti.is_some() {
+                        return Err(de::Error::duplicate_field("cti"));
+                    }
+                    cti = Some(Cow::Borrowed(&**map.next_value::<&'de Bytes>()?));
+                }
+
                 Field::HCERT => {
                     if hcert.is_some() {
                         return Err(de::Error::duplicate_field("hcert"));
                     }
                     hcert = Some(map.next_value()?);
                 }
+
+                Field::Unknown(key) => {
+                    log::debug!(target:"dgc", "Unknown CWT claim: {:?}", key);
+
+                    if unknown_claims.contains_key(&key) {
+                        return Err(duplicate_key(&key));
+                    }
+
+                    let value = map.next_value()?;
+                    unknown_claims.insert(key, value);
+                }
             }
         }
 
@@ -266,13 +781,97 @@ impl<'cose, 'de: 'cose> Visitor<'de> for HCertPayloadVisitor<'cose> {
 
         Ok(HCertPayload {
             iss,
+            sub,
+            aud,
             iat,
             exp,
+            nbf,
+            cti,
             hcert,
+            unknown_claims,
         })
     }
 }
 
+/// Same wording as [`de::Error::duplicate_field`], which only takes static
+/// names.
+fn duplicate_key<E: de::Error>(key: &Value) -> E {
+    match key {
+        Value::Text(text) => E::custom(format_args!("duplicate field `{}`", text)),
+        Value::Integer(n) => E::custom(format_args!("duplicate field `{}`", n)),
+        other => E::custom(format_args!("duplicate field `{:?}`", other)),
+    }
+}
+
+struct HCertVisitor<'v> {
+    _lt: PhantomData<&'v ()>,
+}
+
+impl<'cose, 'de: 'cose> Visitor<'de> for HCertVisitor<'cose> {
+    type Value = HCert<'cose>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of health certificates")
+    }
+
+    fn visit_map<V>(self, mut map: V) -> Result<HCert<'cose>, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let mut hcert = HCert::default();
+
+        while let Some(key) = map.next_key::<Value>()? {
+            if key == Value::Integer(1) {
+                if hcert.dcc.is_some() {
+                    return Err(de::Error::duplicate_field("1"));
+                }
+                hcert.dcc = Some(map.next_value()?);
+            } else {
+                log::debug!(target:"dgc", "Other health certificate: {:?}", key);
+
+                if hcert.others.contains_key(&key) {
+                    return Err(duplicate_key(&key));
+                }
+
+                let value = map.next_value()?;
+                hcert.others.insert(key, value);
+            }
+        }
+
+        Ok(hcert)
+    }
+}
+
+impl<'cose, 'de: 'cose> de::Deserialize<'de> for HCert<'cose> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(HCertVisitor { _lt: PhantomData })
+    }
+}
+
+/// Serialized with the EU DCC under `1`, followed by the other certificates
+/// converted as described in [`Representable`].
+impl Serialize for HCert<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+
+        if let Some(dcc) = &self.dcc {
+            map.serialize_entry(&1, dcc)?;
+        }
+
+        for (key, value) in &self.others {
+            map.serialize_entry(&map_key::<S::Error>(key)?, &Representable(value))?;
+        }
+
+        map.end()
+    }
+}
+
 impl<'cose, 'de: 'cose> de::Deserialize<'de> for HCertPayload<'cose> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -285,3 +884,430 @@ impl<'cose, 'de: 'cose> de::Deserialize<'de> for HCertPayload<'cose> {
         )
     }
 }
+
+/// Serializes the CWT claims with their names rather than their CBOR keys,
+/// the certificates being found under `hcert`. Unregistered claims follow
+/// under their key turned into a string, see [`Representable`].
+impl Serialize for HCertPayload<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+
+        map.serialize_entry("iss", &self.iss)?;
+
+        if let Some(sub) = &self.sub {
+            map.serialize_entry("sub", sub)?;
+        }
+
+        if let Some(aud) = &self.aud {
+            map.serialize_entry("aud", aud)?;
+        }
+
+        map.serialize_entry("iat", &self.iat)?;
+        map.serialize_entry("exp", &self.exp)?;
+
+        if let Some(nbf) = &self.nbf {
+            map.serialize_entry("nbf", nbf)?;
+        }
+
+        if let Some(cti) = &self.cti {
+            map.serialize_entry("cti", &hex(cti))?;
+        }
+
+        map.serialize_entry("hcert", &self.hcert)?;
+
+        for (key, value) in &self.unknown_claims {
+            map.serialize_entry(&map_key::<S::Error>(key)?, &Representable(value))?;
+        }
+
+        map.end()
+    }
+}
+
+/// CBOR value serialized with a representation JSON supports: byte strings
+/// as hex and map keys as strings, tags being left out.
+struct Representable<'a>(&'a Value);
+
+impl Serialize for Representable<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Value::Bytes(bytes) => serializer.serialize_str(&hex(bytes)),
+            Value::Array(items) => serializer.collect_seq(items.iter().map(Representable)),
+            Value::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+
+                for (key, value) in entries {
+                    map.serialize_entry(&map_key::<S::Error>(key)?, &Representable(value))?;
+                }
+
+                map.end()
+            }
+            Value::Tag(_, value) => Representable(value).serialize(serializer),
+            other => other.serialize(serializer),
+        }
+    }
+}
+
+fn serialize_unknown_fields<S>(
+    fields: &BTreeMap<String, Value>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let fields = fields
+        .iter()
+        .map(|(key, value)| (key, Representable(value)));
+
+    serializer.collect_map(fields)
+}
+
+/// Text, integer and byte string keys, the latter as hex. Other keys have
+/// no sensible representation and fail the serialization.
+fn map_key<E: ser::Error>(key: &Value) -> Result<String, E> {
+    match key {
+        Value::Text(text) => Ok(text.clone()),
+        Value::Integer(n) => Ok(n.to_string()),
+        Value::Bytes(bytes) => Ok(hex(bytes)),
+        other => Err(E::custom(format!("unrepresentable map key: {:?}", other))),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::extension::{ExtensionError, NationalEntry};
+
+    fn text(s: &str) -> Value {
+        Value::Text(s.to_owned())
+    }
+
+    fn map(entries: Vec<(Value, Value)>) -> Value {
+        Value::Map(entries.into_iter().collect())
+    }
+
+    fn dcc() -> serde_json::Value {
+        json!({
+            "ver": "1.3.0",
+            "dob": "1990-01-01",
+            "nam": { "fn": "Dupont", "fnt": "DUPONT", "gnt": "JEAN" },
+            "r": [{
+                "tg": "840539006",
+                "fr": "2021-12-01",
+                "df": "2021-12-12",
+                "du": "2022-05-30",
+                "co": "FR",
+                "is": "Ministère de la Santé",
+                "ci": "URN:UVCI:01:FR:W7V2BE46QSBJ#L"
+            }]
+        })
+    }
+
+    fn claims() -> Vec<(Value, Value)> {
+        let dcc = serde_cbor::value::to_value(dcc()).unwrap();
+
+        let other = map(vec![(text("id"), Value::Bytes(vec![0xff]))]);
+        let hcert = map(vec![(Value::Integer(1), dcc), (Value::Integer(2), other)]);
+        let national = map(vec![(
+            Value::Bytes(vec![0x01]),
+            Value::Tag(1, Box::new(Value::Integer(2))),
+        )]);
+
+        vec![
+            (Value::Integer(1), text("FR")),
+            (Value::Integer(4), Value::Integer(1_700_000_000)),
+            (Value::Integer(6), Value::Integer(1_600_000_000)),
+            (Value::Integer(7), Value::Bytes(vec![0xca, 0xfe])),
+            (Value::Integer(-260), hcert),
+            (Value::Integer(-261), national),
+        ]
+    }
+
+    fn payload() -> Vec<u8> {
+        serde_cbor::to_vec(&map(claims())).unwrap()
+    }
+
+    /// CBOR map of `claims`, which may hold the same key several times.
+    fn raw_payload(claims: &[(Value, Value)]) -> Vec<u8> {
+        let mut buf = vec![0xa0 | claims.len() as u8];
+
+        for (key, value) in claims {
+            buf.extend(serde_cbor::to_vec(key).unwrap());
+            buf.extend(serde_cbor::to_vec(value).unwrap());
+        }
+
+        buf
+    }
+
+    fn national_entry(_: &Value) -> Result<Box<dyn NationalEntry>, ExtensionError> {
+        Ok(Box::new("national"))
+    }
+
+    #[test]
+    fn national_entries_need_extensions() {
+        let mut dcc = dcc();
+        dcc["nl"] = json!({ "id": 1 });
+
+        let mut extensions = Extensions::new();
+        extensions.register("nl", national_entry);
+
+        let recovery = dcc.to_string();
+        let recovery: CertificateData = serde_json::from_str(&recovery).unwrap();
+
+        assert!(matches!(recovery.kind(), Ok(CertificateKind::Recovery(_))));
+        assert_eq!(
+            recovery.kind_with(&extensions),
+            Err(CertificateKindError::SeveralEntries)
+        );
+
+        dcc.as_object_mut().unwrap().remove("r");
+
+        let national = dcc.to_string();
+        let national: CertificateData = serde_json::from_str(&national).unwrap();
+
+        assert_eq!(national.kind(), Err(CertificateKindError::NoEntry));
+        assert!(matches!(
+            national.kind_with(&extensions),
+            Ok(CertificateKind::National { key: "nl", .. })
+        ));
+    }
+
+    #[test]
+    fn serializes_every_claim() {
+        let payload = payload();
+        let hcert: HCertPayload = serde_cbor::from_slice(&payload).unwrap();
+
+        let json = serde_json::to_value(&hcert).unwrap();
+
+        assert_eq!(
+            json,
+            json!({
+                "iss": "FR",
+                "iat": 1_600_000_000,
+                "exp": 1_700_000_000,
+                "cti": "cafe",
+                "hcert": { "1": dcc(), "2": { "id": "ff" } },
+                "-261": { "01": 2 }
+            })
+        );
+
+        let dcc = json["hcert"]["1"].to_string();
+        let dcc: CertificateData = serde_json::from_str(&dcc).unwrap();
+
+        assert_eq!(hcert.certificate(), Some(&dcc));
+    }
+
+    #[test]
+    fn serializes_unknown_fields() {
+        let json = dcc().to_string();
+        let mut cert: CertificateData = serde_json::from_str(&json).unwrap();
+
+        let national = map(vec![(Value::Integer(1), Value::Bytes(vec![0xff]))]);
+        cert.unknown_fields.insert("nl".to_owned(), national);
+
+        let json = serde_json::to_value(&cert).unwrap();
+
+        assert_eq!(json["nl"], json!({ "1": "ff" }));
+        assert_eq!(json["r"], dcc()["r"]);
+    }
+
+    #[test]
+    fn fails_on_unrepresentable_keys() {
+        let payload = payload();
+        let mut hcert: HCertPayload = serde_cbor::from_slice(&payload).unwrap();
+
+        let unrepresentable = map(vec![(Value::Null, Value::Null)]);
+
+        hcert
+            .hcert
+            .others
+            .insert(Value::Integer(3), unrepresentable.clone());
+
+        assert!(serde_json::to_value(&hcert).is_err());
+
+        hcert.hcert.others.clear();
+        hcert
+            .unknown_claims
+            .insert(Value::Integer(-262), unrepresentable);
+
+        assert!(serde_json::to_value(&hcert).is_err());
+    }
+
+    #[test]
+    fn parses_registered_claims() {
+        let mut registered = claims();
+        registered.extend([
+            (Value::Integer(2), text("subject")),
+            (Value::Integer(3), text("audience")),
+            (Value::Integer(5), Value::Integer(1_650_000_000)),
+        ]);
+
+        let payload = raw_payload(&registered);
+        let hcert: HCertPayload = serde_cbor::from_slice(&payload).unwrap();
+
+        assert_eq!(hcert.iss, "FR");
+        assert_eq!(hcert.sub.as_deref(), Some("subject"));
+        assert_eq!(hcert.aud.as_deref(), Some("audience"));
+        assert_eq!(hcert.nbf, Some(1_650_000_000));
+        assert_eq!(hcert.not_before(), Some(Utc.timestamp(1_650_000_000, 0)));
+        assert_eq!(hcert.cti.as_deref(), Some(&[0xca, 0xfe][..]));
+        assert_eq!(
+            hcert.unknown_claims.keys().collect::<Vec<_>>(),
+            vec![&Value::Integer(-261)]
+        );
+
+        let json = serde_json::to_value(&hcert).unwrap();
+
+        assert_eq!(json["sub"], "subject");
+        assert_eq!(json["aud"], "audience");
+        assert_eq!(json["nbf"], 1_650_000_000);
+        assert_eq!(json["cti"], "cafe");
+
+        let required: Vec<_> = claims()
+            .into_iter()
+            .filter(|(key, _)| [1, 4, 6, -260].map(Value::Integer).contains(key))
+            .collect();
+
+        let payload = raw_payload(&required);
+        let hcert: HCertPayload = serde_cbor::from_slice(&payload).unwrap();
+
+        assert_eq!(
+            (hcert.sub, hcert.aud, hcert.nbf, hcert.cti),
+            (None, None, None, None)
+        );
+        assert!(hcert.unknown_claims.is_empty());
+    }
+
+    #[test]
+    fn rejects_duplicate_claims() {
+        let duplicate = |claim: (Value, Value)| {
+            let mut claims = claims();
+            claims.push(claim);
+
+            let payload = raw_payload(&claims);
+
+            serde_cbor::from_slice::<HCertPayload>(&payload)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(duplicate((Value::Integer(1), text("DE"))).contains("duplicate field `iss`"));
+        assert!(duplicate((Value::Integer(7), Value::Bytes(vec![0x01])))
+            .contains("duplicate field `cti`"));
+        assert!(duplicate((Value::Integer(-261), Value::Null)).contains("duplicate field `-261`"));
+    }
+
+    fn vaccination() -> serde_json::Value {
+        json!([{
+            "tg": "840539006",
+            "vp": "1119349007",
+            "mp": "EU/1/20/1528",
+            "ma": "ORG-100030215",
+            "dn": 2,
+            "sd": 2,
+            "dt": "2021-06-01",
+            "co": "FR",
+            "is": "CNAM",
+            "ci": "URN:UVCI:01:FR:VACCINATION#A"
+        }])
+    }
+
+    fn test() -> serde_json::Value {
+        json!([{
+            "tg": "840539006",
+            "tt": "LP6464-4",
+            "nm": "Roche LightCycler qPCR",
+            "sc": "2021-06-01T10:00:00Z",
+            "tr": "260415000",
+            "tc": "Laboratoire",
+            "co": "FR",
+            "is": "CNAM",
+            "ci": "URN:UVCI:01:FR:TEST#B"
+        }])
+    }
+
+    /// `dcc` with its recovery entry replaced by `entries`.
+    fn with_entries(entries: &[(&str, serde_json::Value)]) -> String {
+        let mut dcc = dcc();
+        dcc.as_object_mut().unwrap().remove("r");
+
+        for (key, entry) in entries {
+            dcc[*key] = entry.clone();
+        }
+
+        dcc.to_string()
+    }
+
+    #[test]
+    fn tells_the_kind_of_single_entries() {
+        let json = with_entries(&[("v", vaccination())]);
+        let data: CertificateData = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(
+            data.kind(),
+            Ok(CertificateKind::Vaccination(v)) if v.ci == "URN:UVCI:01:FR:VACCINATION#A"
+        ));
+
+        let json = with_entries(&[("t", test())]);
+        let data: CertificateData = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(
+            data.kind(),
+            Ok(CertificateKind::Test(t)) if t.ci == "URN:UVCI:01:FR:TEST#B"
+        ));
+
+        let json = dcc().to_string();
+        let data: CertificateData = serde_json::from_str(&json).unwrap();
+
+        assert!(matches!(
+            data.kind(),
+            Ok(CertificateKind::Recovery(r)) if r.ci == "URN:UVCI:01:FR:W7V2BE46QSBJ#L"
+        ));
+    }
+
+    #[test]
+    fn fails_without_entries() {
+        let json = with_entries(&[]);
+        let data: CertificateData = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(data.kind(), Err(CertificateKindError::NoEntry));
+
+        let payload = payload();
+        let hcert = HCertPayload {
+            hcert: HCert::default(),
+            ..serde_cbor::from_slice(&payload).unwrap()
+        };
+
+        assert_eq!(
+            hcert.kind().unwrap_err(),
+            CertificateKindError::MissingCertificateData
+        );
+    }
+
+    #[test]
+    fn fails_with_several_entries() {
+        let recovery = dcc()["r"].clone();
+
+        for entries in [
+            [("v", vaccination()), ("t", test())],
+            [("v", vaccination()), ("r", recovery.clone())],
+            [("t", test()), ("r", recovery)],
+        ] {
+            let json = with_entries(&entries);
+            let data: CertificateData = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(data.kind(), Err(CertificateKindError::SeveralEntries));
+        }
+    }
+}
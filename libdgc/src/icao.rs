@@ -0,0 +1,316 @@
+//! Transliteration of names into the machine readable form of ICAO Doc 9303
+//! part 3, as used by the standardized `fnt` and `gnt` fields.
+
+/// Length of the name field in the MRZ of a passport (TD3).
+pub const TD3_NAME_LENGTH: usize = 39;
+
+const FILLER: char = '<';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcaoError {
+    /// No transliteration is defined for this character
+    UnsupportedCharacter(char),
+}
+
+/// Transliterations of an uppercase character, the recommended one first.
+///
+/// Some characters, like `Ä`, are also commonly written without their
+/// diacritic, which Doc 9303 allows when the issuer chooses so.
+fn transliterations(c: char) -> Option<&'static [&'static str]> {
+    let options: &'static [&'static str] = match c {
+        'A'..='Z' => ascii(c),
+
+        // Separators and punctuation
+        ' ' | '-' => &["<"],
+        '\'' | '’' | ',' | '.' => &[""],
+
+        // Latin
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ā' | 'Ă' | 'Ą' => &["A"],
+        'Ä' => &["AE", "A"],
+        'Å' => &["AA", "A"],
+        'Æ' => &["AE"],
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => &["C"],
+        'Ð' | 'Ď' | 'Đ' => &["D"],
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => &["E"],
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => &["G"],
+        'Ĥ' | 'Ħ' => &["H"],
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => &["I"],
+        'Ĳ' => &["IJ"],
+        'Ĵ' => &["J"],
+        'Ķ' | 'ĸ' => &["K"],
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => &["L"],
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' | 'Ŋ' => &["N"],
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ō' | 'Ŏ' | 'Ő' => &["O"],
+        'Ö' => &["OE", "O"],
+        'Ø' | 'Œ' => &["OE"],
+        'Ŕ' | 'Ŗ' | 'Ř' => &["R"],
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' | 'Ș' => &["S"],
+        'Ţ' | 'Ť' | 'Ŧ' | 'Ț' => &["T"],
+        'Þ' => &["TH"],
+        'Ù' | 'Ú' | 'Û' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => &["U"],
+        'Ü' => &["UE", "U"],
+        'Ŵ' => &["W"],
+        'Ý' | 'Ŷ' | 'Ÿ' => &["Y"],
+        'Ź' | 'Ż' | 'Ž' => &["Z"],
+
+        // Cyrillic
+        'А' => &["A"],
+        'Б' => &["B"],
+        'В' => &["V"],
+        'Г' => &["G", "H"],
+        'Ґ' | 'Ѓ' => &["G"],
+        'Д' | 'Ђ' => &["D"],
+        'Е' | 'Ё' | 'Э' => &["E"],
+        'Є' | 'Ъ' => &["IE"],
+        'Ж' => &["ZH"],
+        'З' => &["Z"],
+        'Ѕ' | 'Џ' => &["DZ"],
+        'И' | 'І' | 'Ї' | 'Й' => &["I"],
+        'Ј' => &["J"],
+        'К' | 'Ќ' => &["K"],
+        'Л' => &["L"],
+        'Љ' => &["LJ"],
+        'М' => &["M"],
+        'Н' => &["N"],
+        'Њ' => &["NJ"],
+        'О' => &["O"],
+        'П' => &["P"],
+        'Р' => &["R"],
+        'С' => &["S"],
+        'Т' => &["T"],
+        'Ћ' => &["C"],
+        'У' | 'Ў' => &["U"],
+        'Ф' => &["F"],
+        'Х' => &["KH"],
+        'Ц' => &["TS"],
+        'Ч' => &["CH"],
+        'Ш' => &["SH"],
+        'Щ' => &["SHCH"],
+        'Ы' => &["Y"],
+        'Ь' => &[""],
+        'Ю' => &["IU"],
+        'Я' => &["IA"],
+
+        // Greek
+        'Α' | 'Ά' => &["A"],
+        'Β' => &["V"],
+        'Γ' => &["G"],
+        'Δ' => &["D"],
+        'Ε' | 'Έ' => &["E"],
+        'Ζ' => &["Z"],
+        'Η' | 'Ή' | 'Ι' | 'Ί' | 'Ϊ' => &["I"],
+        'Θ' => &["TH"],
+        'Κ' => &["K"],
+        'Λ' => &["L"],
+        'Μ' => &["M"],
+        'Ν' => &["N"],
+        'Ξ' => &["X"],
+        'Ο' | 'Ό' | 'Ω' | 'Ώ' => &["O"],
+        'Π' => &["P"],
+        'Ρ' => &["R"],
+        'Σ' => &["S"],
+        'Τ' => &["T"],
+        'Υ' | 'Ύ' | 'Ϋ' => &["Y"],
+        'Φ' => &["F"],
+        'Χ' => &["CH"],
+        'Ψ' => &["PS"],
+
+        _ => return None,
+    };
+
+    Some(options)
+}
+
+/// Every transliteration of each character of `name`, uppercased first.
+fn options(name: &str) -> Result<Vec<&'static [&'static str]>, IcaoError> {
+    let mut options = vec![];
+
+    for c in name.trim().chars().flat_map(char::to_uppercase) {
+        options.push(transliterations(c).ok_or(IcaoError::UnsupportedCharacter(c))?);
+    }
+
+    Ok(options)
+}
+
+fn ascii(c: char) -> &'static [&'static str] {
+    const LETTERS: [&str; 26] = [
+        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O", "P", "Q", "R",
+        "S", "T", "U", "V", "W", "X", "Y", "Z",
+    ];
+
+    let i = (c as u8 - b'A') as usize;
+
+    &LETTERS[i..=i]
+}
+
+/// Runs of fillers are collapsed into one, and leading or trailing ones
+/// removed, as issuers disagree on how to handle repeated separators.
+fn normalize(name: &str) -> String {
+    name.split(FILLER)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("<")
+}
+
+/// Recommended transliteration of `name`, e.g. `Müller-Schäfer` becomes
+/// `MUELLER<SCHAEFER`.
+pub fn transliterate(name: &str) -> Result<String, IcaoError> {
+    let transliterated: String = options(name)?.iter().map(|options| options[0]).collect();
+
+    Ok(normalize(&transliterated))
+}
+
+/// Whether `standardized` is a valid transliteration of `name`, accepting
+/// the alternatives allowed by Doc 9303.
+pub fn is_transliteration_of(standardized: &str, name: &str) -> Result<bool, IcaoError> {
+    let mut expected: Vec<&[&str]> = vec![];
+
+    // Fillers are collapsed and trimmed like in `normalize`.
+    for options in options(name)? {
+        if options[0] == "<" {
+            let after_filler = expected.iter().rev().find(|options| !options[0].is_empty());
+
+            if matches!(after_filler, None | Some(["<"])) {
+                continue;
+            }
+        }
+
+        expected.push(options);
+    }
+
+    while let Some(["<"]) | Some([""]) = expected.last() {
+        expected.pop();
+    }
+
+    Ok(matches(&normalize(standardized), &expected))
+}
+
+fn matches(standardized: &str, options: &[&[&str]]) -> bool {
+    let (first, rest) = match options.split_first() {
+        Some(split) => split,
+        None => return standardized.is_empty(),
+    };
+
+    first.iter().any(|option| {
+        standardized
+            .strip_prefix(option)
+            .map(|remaining| matches(remaining, rest))
+            .unwrap_or(false)
+    })
+}
+
+/// Name as written in a machine readable zone: the primary identifier,
+/// followed by `<<` and the secondary identifier if any.
+pub fn mrz_name(primary: &str, secondary: Option<&str>) -> String {
+    let primary = normalize(primary);
+
+    match secondary.map(normalize) {
+        Some(secondary) if !secondary.is_empty() => format!("{}<<{}", primary, secondary),
+        _ => primary,
+    }
+}
+
+/// Pads `name` with fillers, or truncates it, to fit a field of `length`
+/// characters, e.g. [`TD3_NAME_LENGTH`].
+pub fn mrz_field(name: &str, length: usize) -> String {
+    let name: String = name.chars().take(length).collect();
+
+    format!("{:<<1$}", name, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transliterates_latin_names() {
+        assert_eq!(transliterate("Müller-Schäfer").unwrap(), "MUELLER<SCHAEFER");
+        assert_eq!(transliterate("Anna Maria").unwrap(), "ANNA<MARIA");
+        assert_eq!(transliterate("D'Artagnan").unwrap(), "DARTAGNAN");
+        assert_eq!(transliterate("Ødegård").unwrap(), "OEDEGAARD");
+        assert_eq!(transliterate("Œuvré").unwrap(), "OEUVRE");
+    }
+
+    #[test]
+    fn umlauts_may_lose_their_diacritic() {
+        for standardized in ["JAEGER", "JAGER"] {
+            assert_eq!(is_transliteration_of(standardized, "Jäger"), Ok(true));
+        }
+
+        assert_eq!(transliterate("Jäger").unwrap(), "JAEGER");
+        assert_eq!(is_transliteration_of("JAEGAR", "Jäger"), Ok(false));
+
+        // The Danish and Norwegian letters always have two.
+        assert_eq!(is_transliteration_of("ODEGARD", "Ødegård"), Ok(false));
+        assert_eq!(is_transliteration_of("OEDEGARD", "Ødegård"), Ok(true));
+    }
+
+    #[test]
+    fn transliterates_cyrillic_names() {
+        assert_eq!(transliterate("Горбачёв").unwrap(), "GORBACHEV");
+        assert_eq!(
+            transliterate("Михаил Сергеевич").unwrap(),
+            "MIKHAIL<SERGEEVICH"
+        );
+        assert_eq!(transliterate("Щукин").unwrap(), "SHCHUKIN");
+        assert_eq!(transliterate("Юлия").unwrap(), "IULIIA");
+
+        // Ukrainian names write `Г` as `H`.
+        assert_eq!(is_transliteration_of("HANNA", "Ганна"), Ok(true));
+        assert_eq!(is_transliteration_of("GANNA", "Ганна"), Ok(true));
+    }
+
+    #[test]
+    fn transliterates_greek_names() {
+        assert_eq!(transliterate("Αλέξανδρος").unwrap(), "ALEXANDROS");
+        assert_eq!(transliterate("ΘΕΜΙΣΤΟΚΛΗΣ").unwrap(), "THEMISTOKLIS");
+        assert_eq!(transliterate("Ψαρρός").unwrap(), "PSARROS");
+    }
+
+    #[test]
+    fn unsupported_characters_are_reported() {
+        assert_eq!(
+            transliterate("李"),
+            Err(IcaoError::UnsupportedCharacter('李'))
+        );
+    }
+
+    #[test]
+    fn fillers_are_normalized() {
+        assert_eq!(transliterate(" Jean -  Pierre ").unwrap(), "JEAN<PIERRE");
+        assert_eq!(
+            is_transliteration_of("JEAN<<PIERRE<", "Jean - Pierre"),
+            Ok(true)
+        );
+        assert_eq!(
+            is_transliteration_of("JEANPIERRE", "Jean-Pierre"),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn td3_name_field() {
+        let name = mrz_name("ERIKSSON", Some("ANNA<MARIA"));
+
+        assert_eq!(name, "ERIKSSON<<ANNA<MARIA");
+        assert_eq!(
+            mrz_field(&name, TD3_NAME_LENGTH),
+            "ERIKSSON<<ANNA<MARIA<<<<<<<<<<<<<<<<<<<"
+        );
+
+        assert_eq!(mrz_name("ERIKSSON", None), "ERIKSSON");
+        assert_eq!(mrz_name("ERIKSSON", Some("<")), "ERIKSSON");
+    }
+
+    #[test]
+    fn td3_name_field_is_truncated() {
+        let name = mrz_name(
+            "DE<LA<CRUZ<FERNANDEZ<DE<CORDOBA",
+            Some("MARIANELA<DEL<PILAR"),
+        );
+        let field = mrz_field(&name, TD3_NAME_LENGTH);
+
+        assert_eq!(field, "DE<LA<CRUZ<FERNANDEZ<DE<CORDOBA<<MARIAN");
+        assert_eq!(field.chars().count(), TD3_NAME_LENGTH);
+    }
+}
@@ -9,11 +9,21 @@ use image::ImageError;
 use zbars::prelude::*;
 use zbars::ZBarErrorType;
 
+pub mod certlogic;
 mod cose;
 pub mod cwt;
+pub mod date;
 pub mod dgc;
 pub mod display;
+pub mod extension;
 pub mod hcert;
+pub mod icao;
+pub mod lint;
+pub mod policy;
+pub mod revocation;
+pub mod schema;
+pub mod uvci;
+pub mod valueset;
 
 use crate::cose::Generic_Headers;
 use crate::dgc::DigitalGreenCertificate;
@@ -27,8 +37,7 @@ pub enum ImageDecodingFailure<'i> {
     InvalidQRCode(DecodeError<'i>),
 }
 
-type ImageDecodingResult<'i> =
-    Result<Vec<DigitalGreenCertificate<Raw<'i>>>, ImageDecodingFailure<'i>>;
+type ImageDecodingResult<'i> = Result<Vec<DigitalGreenCertificate<Raw>>, ImageDecodingFailure<'i>>;
 
 pub fn decode_image<'i, P: AsRef<Path>>(image_path: P) -> ImageDecodingResult<'i> {
     use ImageDecodingFailure::*;
@@ -0,0 +1,201 @@
+use std::fmt;
+
+use crate::{
+    hcert::CertificateData,
+    icao::{self, IcaoError},
+    uvci::{Uvci, UvciError},
+};
+
+/// Inconsistencies which do not make a certificate invalid, but are worth
+/// reporting to its issuer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lint {
+    /// The certificate identifier is not a well formed UVCI
+    InvalidUvci(UvciError),
+
+    /// The UVCI was issued by another country than the one in `co`
+    UvciCountryMismatch { uvci_country: String, co: String },
+
+    /// A standardized name, e.g. `gnt`, is not the ICAO 9303
+    /// transliteration of the primary one
+    NameMismatch {
+        field: &'static str,
+        standardized: String,
+        expected: String,
+    },
+
+    /// A primary name contains a character which cannot be transliterated,
+    /// so its standardized form could not be checked
+    UntransliterableName {
+        field: &'static str,
+        character: char,
+    },
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::InvalidUvci(e) => write!(f, "invalid certificate identifier: {:?}", e),
+            Lint::UvciCountryMismatch { uvci_country, co } => write!(
+                f,
+                "certificate identifier issued in {} but country is {}",
+                uvci_country, co
+            ),
+            Lint::NameMismatch {
+                field,
+                standardized,
+                expected,
+            } => write!(
+                f,
+                "standardized name {} is {} but should be {}",
+                field, standardized, expected
+            ),
+            Lint::UntransliterableName { field, character } => write!(
+                f,
+                "{} contains '{}' which has no ICAO 9303 transliteration",
+                field, character
+            ),
+        }
+    }
+}
+
+impl CertificateData<'_> {
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = vec![];
+
+        let names = [
+            ("fn", self.nam.sn.as_deref(), "fnt", Some(&*self.nam.fnt)),
+            ("gn", self.nam.gn.as_deref(), "gnt", self.nam.gnt.as_deref()),
+        ];
+
+        for (field, name, standardized_field, standardized) in names {
+            if let (Some(name), Some(standardized)) = (name, standardized) {
+                lints.extend(lint_name(field, name, standardized_field, standardized));
+            }
+        }
+
+        let (ci, co) = match self.identifier_and_country() {
+            Some(identifier_and_country) => identifier_and_country,
+            None => return lints,
+        };
+
+        match ci.parse::<Uvci>() {
+            Ok(uvci) if uvci.country() != co => lints.push(Lint::UvciCountryMismatch {
+                uvci_country: uvci.country().to_owned(),
+                co: co.to_owned(),
+            }),
+            Ok(_) => {}
+            Err(e) => lints.push(Lint::InvalidUvci(e)),
+        }
+
+        lints
+    }
+}
+
+fn lint_name(
+    field: &'static str,
+    name: &str,
+    standardized_field: &'static str,
+    standardized: &str,
+) -> Option<Lint> {
+    match icao::is_transliteration_of(standardized, name) {
+        Ok(true) => None,
+        Ok(false) => icao::transliterate(name)
+            .ok()
+            .map(|expected| Lint::NameMismatch {
+                field: standardized_field,
+                standardized: standardized.to_owned(),
+                expected,
+            }),
+        Err(IcaoError::UnsupportedCharacter(character)) => {
+            Some(Lint::UntransliterableName { field, character })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(nam: serde_json::Value, ci: &str) -> Vec<Lint> {
+        let dcc = serde_json::json!({
+            "ver": "1.3.0",
+            "dob": "1964-08-12",
+            "nam": nam,
+            "r": [{
+                "tg": "840539006",
+                "fr": "2021-12-01",
+                "df": "2021-12-12",
+                "du": "2022-05-30",
+                "co": "DE",
+                "is": "Robert Koch-Institut",
+                "ci": ci
+            }]
+        })
+        .to_string();
+
+        serde_json::from_str::<CertificateData>(&dcc)
+            .unwrap()
+            .lint()
+    }
+
+    #[test]
+    fn consistent_certificates_have_no_lints() {
+        let nam = serde_json::json!({
+            "fn": "Müller", "fnt": "MUELLER", "gn": "Jörg", "gnt": "JORG"
+        });
+
+        assert_eq!(lint(nam, "URN:UVCI:01:DE:187/37512422923"), vec![]);
+    }
+
+    #[test]
+    fn reports_standardized_field_names() {
+        let nam = serde_json::json!({
+            "fn": "Müller", "fnt": "MULLER", "gn": "Jörg", "gnt": "JOERN"
+        });
+
+        let lints = lint(nam, "URN:UVCI:01:DE:187/37512422923");
+
+        assert_eq!(
+            lints,
+            vec![Lint::NameMismatch {
+                field: "gnt",
+                standardized: "JOERN".into(),
+                expected: "JOERG".into(),
+            }]
+        );
+        assert_eq!(
+            lints[0].to_string(),
+            "standardized name gnt is JOERN but should be JOERG"
+        );
+
+        let nam = serde_json::json!({ "fn": "Müller☺", "fnt": "MUELLER" });
+
+        assert_eq!(
+            lint(nam, "URN:UVCI:01:DE:187/37512422923"),
+            vec![Lint::UntransliterableName {
+                field: "fn",
+                character: '☺',
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_identifier_issues() {
+        let nam = serde_json::json!({ "fnt": "MUELLER" });
+
+        assert_eq!(
+            lint(nam.clone(), "URN:UVCI:01:AT:187/37512422923"),
+            vec![Lint::UvciCountryMismatch {
+                uvci_country: "AT".into(),
+                co: "DE".into(),
+            }]
+        );
+        assert_eq!(
+            lint(nam, "187/37512422923"),
+            vec![Lint::InvalidUvci(UvciError::InvalidVersion(
+                "187/37512422923".into()
+            ))]
+        );
+    }
+}
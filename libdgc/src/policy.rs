@@ -0,0 +1,645 @@
+//! Acceptance policies for the usual venue checks, as an alternative to
+//! business rules written in CertLogic.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::{
+    date::DateError,
+    hcert::{CertificateData, CertificateKind, CertificateKindError, Recovery, Test, Vaccine},
+    valueset::{TestResult, TestType},
+};
+
+/// What a certificate proves at the time of the check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Every dose of the primary course, for long enough
+    FullyVaccinated,
+    /// More doses than the primary course, which also makes the holder fully
+    /// vaccinated
+    Boosted,
+    NegativeNaat,
+    NegativeRapidTest,
+    Recovered,
+}
+
+/// Why a certificate proves nothing at the time of the check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejection {
+    InvalidCertificate(CertificateKindError),
+    InvalidDate(DateError),
+    /// Exemptions and national entries
+    UnsupportedKind,
+    IncompleteVaccination {
+        dn: u8,
+        sd: u8,
+    },
+    VaccinationTooRecent {
+        valid_from: NaiveDate,
+    },
+    PositiveTest,
+    UnsupportedTestType(String),
+    TestTooOld {
+        collected_at: DateTime<Utc>,
+    },
+    /// Sample collected after the time of the check
+    TestInFuture {
+        collected_at: DateTime<Utc>,
+    },
+    RecoveryNotYetValid {
+        valid_from: NaiveDate,
+    },
+    RecoveryExpired {
+        valid_until: NaiveDate,
+    },
+    /// Standardized names or date of birth other than those of the first
+    /// certificate presented
+    DifferentHolders,
+}
+
+impl From<DateError> for Rejection {
+    fn from(e: DateError) -> Self {
+        Rejection::InvalidDate(e)
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Rejection::*;
+
+        match self {
+            InvalidCertificate(e) => write!(f, "invalid certificate: {:?}", e),
+            InvalidDate(e) => write!(f, "invalid date: {:?}", e),
+            UnsupportedKind => write!(f, "kind of certificate not accepted"),
+            IncompleteVaccination { dn, sd } => write!(f, "incomplete vaccination ({}/{})", dn, sd),
+            VaccinationTooRecent { valid_from } => {
+                write!(f, "vaccination only valid from {}", valid_from)
+            }
+            PositiveTest => write!(f, "positive test"),
+            UnsupportedTestType(tt) => write!(f, "unsupported test type {}", tt),
+            TestTooOld { collected_at } => write!(f, "test too old ({})", collected_at),
+            TestInFuture { collected_at } => {
+                write!(f, "test collected in the future ({})", collected_at)
+            }
+            RecoveryNotYetValid { valid_from } => {
+                write!(f, "recovery only valid from {}", valid_from)
+            }
+            RecoveryExpired { valid_until } => write!(f, "recovery expired on {}", valid_until),
+            DifferentHolders => write!(f, "certificate of another holder"),
+        }
+    }
+}
+
+/// How long each proof lasts, or how long it takes to become valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Windows {
+    /// Delay after the last dose of the primary course, 14 days by default
+    pub vaccination_delay: Duration,
+    /// Delay after a booster dose, none by default
+    pub booster_delay: Duration,
+    /// 72 hours by default
+    pub naat_validity: Duration,
+    /// 48 hours by default
+    pub rapid_test_validity: Duration,
+    /// Counted from the first positive test, 180 days by default
+    pub recovery_validity: Duration,
+}
+
+impl Default for Windows {
+    fn default() -> Self {
+        Windows {
+            vaccination_delay: Duration::days(14),
+            booster_delay: Duration::zero(),
+            naat_validity: Duration::hours(72),
+            rapid_test_validity: Duration::hours(48),
+            recovery_validity: Duration::days(180),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    FullyVaccinated,
+    Booster,
+    Naat,
+    RapidTest,
+    /// Either a NAAT or a rapid test
+    Tested,
+    Recovered,
+    /// Vaccinated or recovered
+    TwoG,
+    /// Vaccinated, recovered or tested
+    ThreeG,
+    /// Vaccinated or recovered, and tested unless boosted
+    TwoGPlus,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "vaccinated" => Ok(Mode::FullyVaccinated),
+            "booster" => Ok(Mode::Booster),
+            "pcr" | "naat" => Ok(Mode::Naat),
+            "rat" => Ok(Mode::RapidTest),
+            "tested" => Ok(Mode::Tested),
+            "recovered" => Ok(Mode::Recovered),
+            "2g" => Ok(Mode::TwoG),
+            "3g" => Ok(Mode::ThreeG),
+            "2g+" => Ok(Mode::TwoGPlus),
+            _ => Err(format!("unknown policy '{}'", s)),
+        }
+    }
+}
+
+/// Outcome of a policy over the certificates presented together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assessment {
+    pub accepted: bool,
+    /// What the certificates prove
+    pub statuses: Vec<Status>,
+    /// Why the other certificates prove nothing
+    pub rejections: Vec<Rejection>,
+}
+
+/// Dose number from which a vaccination counts as a booster by default.
+pub const BOOSTER_DOSE: u8 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    pub mode: Mode,
+    pub windows: Windows,
+    /// Dose number from which a vaccination counts as a booster even though
+    /// it completes its course, as most countries issue boosters as `3/3`.
+    /// With `None`, only doses beyond the primary course (`dn > sd`) do.
+    /// [`BOOSTER_DOSE`] by default.
+    pub booster_dose: Option<u8>,
+}
+
+impl Policy {
+    /// A policy with the default windows.
+    pub fn new(mode: Mode) -> Self {
+        Self::with_windows(mode, Windows::default())
+    }
+
+    pub fn with_windows(mode: Mode, windows: Windows) -> Self {
+        Policy {
+            mode,
+            windows,
+            booster_dose: Some(BOOSTER_DOSE),
+        }
+    }
+
+    /// Checks the certificates presented by a holder at `at`, several of
+    /// them being needed for 2G+ unless boosted. Certificates of another
+    /// holder than the first one are not counted.
+    pub fn check(&self, certificates: &[&CertificateData], at: DateTime<Utc>) -> Assessment {
+        let mut statuses = vec![];
+        let mut rejections = vec![];
+
+        let first_holder = certificates.first().map(|certificate| holder(certificate));
+
+        for certificate in certificates {
+            if Some(holder(certificate)) != first_holder {
+                rejections.push(Rejection::DifferentHolders);
+                continue;
+            }
+
+            match self.assess(certificate, at) {
+                Ok(status) => statuses.push(status),
+                Err(rejection) => rejections.push(rejection),
+            }
+        }
+
+        let has = |wanted: &[Status]| statuses.iter().any(|status| wanted.contains(status));
+
+        let vaccinated = [Status::FullyVaccinated, Status::Boosted];
+        let tested = [Status::NegativeNaat, Status::NegativeRapidTest];
+
+        let accepted = match self.mode {
+            Mode::FullyVaccinated => has(&vaccinated),
+            Mode::Booster => has(&[Status::Boosted]),
+            Mode::Naat => has(&[Status::NegativeNaat]),
+            Mode::RapidTest => has(&[Status::NegativeRapidTest]),
+            Mode::Tested => has(&tested),
+            Mode::Recovered => has(&[Status::Recovered]),
+            Mode::TwoG => has(&vaccinated) || has(&[Status::Recovered]),
+            Mode::ThreeG => has(&vaccinated) || has(&[Status::Recovered]) || has(&tested),
+            Mode::TwoGPlus => {
+                (has(&vaccinated) || has(&[Status::Recovered]))
+                    && (has(&tested) || has(&[Status::Boosted]))
+            }
+        };
+
+        Assessment {
+            accepted,
+            statuses,
+            rejections,
+        }
+    }
+
+    /// What a single certificate proves at `at`, whatever the mode.
+    pub fn assess(
+        &self,
+        certificate: &CertificateData,
+        at: DateTime<Utc>,
+    ) -> Result<Status, Rejection> {
+        match certificate.kind().map_err(Rejection::InvalidCertificate)? {
+            CertificateKind::Vaccination(v) => self.assess_vaccination(v, at),
+            CertificateKind::Test(t) => self.assess_test(t, at),
+            CertificateKind::Recovery(r) => self.assess_recovery(r, at),
+            CertificateKind::Exemption(_) | CertificateKind::National { .. } => {
+                Err(Rejection::UnsupportedKind)
+            }
+        }
+    }
+
+    fn assess_vaccination(&self, v: &Vaccine, at: DateTime<Utc>) -> Result<Status, Rejection> {
+        let (dn, sd) = (v.dn, v.sd);
+
+        if dn < sd {
+            return Err(Rejection::IncompleteVaccination { dn, sd });
+        }
+
+        let booster = dn > sd || matches!(self.booster_dose, Some(booster) if dn >= booster);
+
+        let (status, delay) = if booster {
+            (Status::Boosted, self.windows.booster_delay)
+        } else {
+            (Status::FullyVaccinated, self.windows.vaccination_delay)
+        };
+
+        let valid_from = v.vaccination_date()? + delay;
+
+        if at.naive_utc().date() < valid_from {
+            return Err(Rejection::VaccinationTooRecent { valid_from });
+        }
+
+        Ok(status)
+    }
+
+    fn assess_test(&self, t: &Test, at: DateTime<Utc>) -> Result<Status, Rejection> {
+        if t.test_result() != TestResult::NotDetected {
+            return Err(Rejection::PositiveTest);
+        }
+
+        let (status, validity) = match t.test_type() {
+            TestType::Naat => (Status::NegativeNaat, self.windows.naat_validity),
+            TestType::RapidAntigen => (Status::NegativeRapidTest, self.windows.rapid_test_validity),
+            TestType::Unknown(tt) => return Err(Rejection::UnsupportedTestType(tt)),
+        };
+
+        let collected_at = t.sample_collected_at()?.with_timezone(&Utc);
+
+        if collected_at > at {
+            return Err(Rejection::TestInFuture { collected_at });
+        }
+
+        if at - collected_at > validity {
+            return Err(Rejection::TestTooOld { collected_at });
+        }
+
+        Ok(status)
+    }
+
+    fn assess_recovery(&self, r: &Recovery, at: DateTime<Utc>) -> Result<Status, Rejection> {
+        let today = at.naive_utc().date();
+
+        let valid_from = r.valid_from()?;
+
+        if today < valid_from {
+            return Err(Rejection::RecoveryNotYetValid { valid_from });
+        }
+
+        let valid_until = r
+            .valid_until()?
+            .min(r.first_positive_test_date()? + self.windows.recovery_validity);
+
+        if today > valid_until {
+            return Err(Rejection::RecoveryExpired { valid_until });
+        }
+
+        Ok(Status::Recovered)
+    }
+}
+
+/// Standardized names and date of birth of the holder.
+fn holder<'a>(certificate: &'a CertificateData) -> (&'a str, Option<&'a str>, &'a str) {
+    (
+        &certificate.nam.fnt,
+        certificate.nam.gnt.as_deref(),
+        &certificate.dob,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::{json, Value as Json};
+
+    use super::*;
+
+    fn certificate(entry: &str, value: Json) -> CertificateData<'static> {
+        let mut dcc = json!({
+            "ver": "1.3.0",
+            "dob": "1990-01-01",
+            "nam": { "fnt": "DUPONT", "gnt": "JEAN" },
+        });
+        dcc[entry] = json!([value]);
+
+        serde_json::from_str::<CertificateData>(&dcc.to_string())
+            .unwrap()
+            .into_owned()
+    }
+
+    fn vaccination(dn: u8, sd: u8, dt: &str) -> CertificateData<'static> {
+        certificate(
+            "v",
+            json!({
+                "tg": "840539006",
+                "vp": "1119349007",
+                "mp": "EU/1/20/1528",
+                "ma": "ORG-100030215",
+                "dn": dn,
+                "sd": sd,
+                "dt": dt,
+                "co": "FR",
+                "is": "CNAM",
+                "ci": "URN:UVCI:01:FR:V"
+            }),
+        )
+    }
+
+    fn test(tt: &str, tr: &str, sc: &str) -> CertificateData<'static> {
+        certificate(
+            "t",
+            json!({
+                "tg": "840539006",
+                "tt": tt,
+                "sc": sc,
+                "tr": tr,
+                "co": "FR",
+                "is": "CNAM",
+                "ci": "URN:UVCI:01:FR:T"
+            }),
+        )
+    }
+
+    fn naat(sc: &str) -> CertificateData<'static> {
+        test("LP6464-4", "260415000", sc)
+    }
+
+    fn rapid_test(sc: &str) -> CertificateData<'static> {
+        test("LP217198-3", "260415000", sc)
+    }
+
+    fn recovery(fr: &str) -> CertificateData<'static> {
+        certificate(
+            "r",
+            json!({
+                "tg": "840539006",
+                "fr": fr,
+                "df": fr,
+                "du": "2030-01-01",
+                "co": "FR",
+                "is": "CNAM",
+                "ci": "URN:UVCI:01:FR:R"
+            }),
+        )
+    }
+
+    fn at(date_time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date_time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn assess(certificate: &CertificateData, date_time: &str) -> Result<Status, Rejection> {
+        Policy::new(Mode::ThreeG).assess(certificate, at(date_time))
+    }
+
+    #[test]
+    fn accepts_what_each_mode_requires() {
+        let now = at("2022-01-15T12:00:00Z");
+
+        let vaccinated = vaccination(2, 2, "2021-12-01");
+        let boosted = vaccination(3, 3, "2022-01-10");
+        let naat = naat("2022-01-14T12:00:00Z");
+        let rapid_test = rapid_test("2022-01-15T08:00:00Z");
+        let recovered = recovery("2021-11-01");
+
+        let accepted = |mode, certificates: &[&CertificateData]| {
+            Policy::new(mode).check(certificates, now).accepted
+        };
+
+        for (mode, accepting) in [
+            (Mode::FullyVaccinated, vec![&vaccinated, &boosted]),
+            (Mode::Booster, vec![&boosted]),
+            (Mode::Naat, vec![&naat]),
+            (Mode::RapidTest, vec![&rapid_test]),
+            (Mode::Tested, vec![&naat, &rapid_test]),
+            (Mode::Recovered, vec![&recovered]),
+            (Mode::TwoG, vec![&vaccinated, &boosted, &recovered]),
+            (
+                Mode::ThreeG,
+                vec![&vaccinated, &boosted, &naat, &rapid_test, &recovered],
+            ),
+            (Mode::TwoGPlus, vec![&boosted]),
+        ] {
+            for certificate in [&vaccinated, &boosted, &naat, &rapid_test, &recovered] {
+                let expected = accepting
+                    .iter()
+                    .any(|accepted| std::ptr::eq(*accepted, certificate));
+
+                assert_eq!(
+                    accepted(mode, &[certificate]),
+                    expected,
+                    "{:?} {:?}",
+                    mode,
+                    certificate.kind()
+                );
+            }
+        }
+
+        for proof in [&vaccinated, &recovered] {
+            for test in [&naat, &rapid_test] {
+                assert!(accepted(Mode::TwoGPlus, &[proof, test]));
+            }
+        }
+        assert!(!accepted(Mode::TwoGPlus, &[&vaccinated, &recovered]));
+        assert!(!accepted(Mode::TwoGPlus, &[&naat, &rapid_test]));
+    }
+
+    #[test]
+    fn vaccination_is_valid_14_days_after_the_last_dose() {
+        let vaccinated = vaccination(2, 2, "2022-01-01");
+
+        assert_eq!(
+            assess(&vaccinated, "2022-01-14T23:59:59Z"),
+            Err(Rejection::VaccinationTooRecent {
+                valid_from: NaiveDate::from_ymd(2022, 1, 15)
+            })
+        );
+        assert_eq!(
+            assess(&vaccinated, "2022-01-15T00:00:00Z"),
+            Ok(Status::FullyVaccinated)
+        );
+        assert_eq!(
+            assess(&vaccination(1, 2, "2021-06-01"), "2022-01-15T00:00:00Z"),
+            Err(Rejection::IncompleteVaccination { dn: 1, sd: 2 })
+        );
+    }
+
+    #[test]
+    fn booster_dose_is_configurable() {
+        let now = at("2022-01-15T00:00:00Z");
+
+        let mut policy = Policy::new(Mode::Booster);
+
+        assert_eq!(
+            policy.assess(&vaccination(3, 3, "2022-01-14"), now),
+            Ok(Status::Boosted)
+        );
+        assert_eq!(
+            policy.assess(&vaccination(2, 2, "2021-12-01"), now),
+            Ok(Status::FullyVaccinated)
+        );
+
+        policy.booster_dose = None;
+
+        assert_eq!(
+            policy.assess(&vaccination(3, 3, "2021-12-01"), now),
+            Ok(Status::FullyVaccinated)
+        );
+        assert_eq!(
+            policy.assess(&vaccination(2, 1, "2022-01-14"), now),
+            Ok(Status::Boosted)
+        );
+
+        policy.booster_dose = Some(2);
+
+        assert_eq!(
+            policy.assess(&vaccination(2, 2, "2022-01-14"), now),
+            Ok(Status::Boosted)
+        );
+    }
+
+    #[test]
+    fn naat_is_valid_for_72_hours() {
+        let naat = naat("2022-01-12T12:00:00Z");
+
+        assert_eq!(
+            assess(&naat, "2022-01-15T12:00:00Z"),
+            Ok(Status::NegativeNaat)
+        );
+        assert_eq!(
+            assess(&naat, "2022-01-15T12:00:01Z"),
+            Err(Rejection::TestTooOld {
+                collected_at: Utc.ymd(2022, 1, 12).and_hms(12, 0, 0)
+            })
+        );
+    }
+
+    #[test]
+    fn rapid_test_is_valid_for_48_hours() {
+        // Offsets are taken into account.
+        let rapid_test = rapid_test("2022-01-13T13:00:00+01:00");
+
+        assert_eq!(
+            assess(&rapid_test, "2022-01-15T12:00:00Z"),
+            Ok(Status::NegativeRapidTest)
+        );
+        assert_eq!(
+            assess(&rapid_test, "2022-01-15T12:00:01Z"),
+            Err(Rejection::TestTooOld {
+                collected_at: Utc.ymd(2022, 1, 13).and_hms(12, 0, 0)
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_tests_in_the_future_and_positive_ones() {
+        let naat = naat("2022-01-15T12:00:00Z");
+
+        assert_eq!(
+            assess(&naat, "2022-01-15T12:00:00Z"),
+            Ok(Status::NegativeNaat)
+        );
+        assert_eq!(
+            assess(&naat, "2022-01-15T11:59:59Z"),
+            Err(Rejection::TestInFuture {
+                collected_at: Utc.ymd(2022, 1, 15).and_hms(12, 0, 0)
+            })
+        );
+        assert_eq!(
+            assess(
+                &test("LP6464-4", "260373001", "2022-01-15T08:00:00Z"),
+                "2022-01-15T12:00:00Z"
+            ),
+            Err(Rejection::PositiveTest)
+        );
+        assert_eq!(
+            assess(
+                &test("94558-4", "260415000", "2022-01-15T08:00:00Z"),
+                "2022-01-15T12:00:00Z"
+            ),
+            Err(Rejection::UnsupportedTestType("94558-4".into()))
+        );
+    }
+
+    #[test]
+    fn recovery_is_valid_for_180_days_after_the_first_positive_test() {
+        let recovered = recovery("2021-07-01");
+
+        assert_eq!(
+            assess(&recovered, "2021-12-28T23:59:59Z"),
+            Ok(Status::Recovered)
+        );
+        assert_eq!(
+            assess(&recovered, "2021-12-29T00:00:00Z"),
+            Err(Rejection::RecoveryExpired {
+                valid_until: NaiveDate::from_ymd(2021, 12, 28)
+            })
+        );
+        assert_eq!(
+            assess(&recovered, "2021-06-30T12:00:00Z"),
+            Err(Rejection::RecoveryNotYetValid {
+                valid_from: NaiveDate::from_ymd(2021, 7, 1)
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_certificates_of_other_holders() {
+        let now = at("2022-01-15T12:00:00Z");
+        let policy = Policy::new(Mode::TwoGPlus);
+
+        let vaccinated = vaccination(2, 2, "2021-12-01");
+
+        for (field, value) in [
+            ("/nam/fnt", json!("MARTIN")),
+            ("/nam/gnt", json!("JEANNE")),
+            ("/nam/gnt", json!(null)),
+            ("/dob", json!("1990-01-02")),
+        ] {
+            let mut dcc = serde_json::to_value(naat("2022-01-15T08:00:00Z")).unwrap();
+            *dcc.pointer_mut(field).unwrap() = value;
+
+            let other = serde_json::from_str::<CertificateData>(&dcc.to_string())
+                .unwrap()
+                .into_owned();
+
+            let assessment = policy.check(&[&vaccinated, &other], now);
+
+            assert!(!assessment.accepted, "{}", field);
+            assert_eq!(assessment.statuses, vec![Status::FullyVaccinated]);
+            assert_eq!(assessment.rejections, vec![Rejection::DifferentHolders]);
+        }
+
+        let same = naat("2022-01-15T08:00:00Z");
+
+        assert!(policy.check(&[&vaccinated, &same], now).accepted);
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::RevocationError;
+
+type UvciHash = [u8; 32];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocklistEntry {
+    /// Matched the SHA-256 hash of the UVCI
+    Hashed,
+
+    /// Matched the UVCI itself
+    Plain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlocklistStatus {
+    NotListed,
+    Listed(BlocklistEntry),
+}
+
+/// Hashed blocklist, as distributed by TousAntiCovid.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HashedList {
+    Elements { elements: Vec<HashedElement> },
+    Hashes(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct HashedElement {
+    hash: String,
+}
+
+/// Certificate identifiers known to be fraudulent, either as hex encoded
+/// SHA-256 hashes of the UVCI (TousAntiCovid format) or as plain UVCIs.
+#[derive(Default)]
+pub struct Blocklist {
+    hashes: HashSet<UvciHash>,
+    uvcis: HashSet<String>,
+}
+
+impl Blocklist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads hashed entries, either as TousAntiCovid JSON
+    /// (`{"elements": [{"hash": "..."}]}` or an array of hashes) or as text,
+    /// one hash per line.
+    pub fn load_hashed_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), RevocationError> {
+        let file = File::open(path).map_err(RevocationError::FileError)?;
+
+        self.load_hashed(BufReader::new(file))
+    }
+
+    pub fn load_hashed<R: Read>(&mut self, mut r: R) -> Result<(), RevocationError> {
+        let mut content = String::new();
+        r.read_to_string(&mut content)
+            .map_err(RevocationError::FileError)?;
+
+        let hashes: Vec<String> = match content.trim_start().chars().next() {
+            Some('{') | Some('[') => {
+                match serde_json::from_str(&content).map_err(RevocationError::ParsingError)? {
+                    HashedList::Elements { elements } => {
+                        elements.into_iter().map(|element| element.hash).collect()
+                    }
+                    HashedList::Hashes(hashes) => hashes,
+                }
+            }
+            _ => entries(&content).map(str::to_owned).collect(),
+        };
+
+        for hash in &hashes {
+            self.hashes.insert(parse_hash(hash)?);
+        }
+
+        log::debug!(target:"revocation", "Loaded {} hashed blocklist entries.", hashes.len());
+
+        Ok(())
+    }
+
+    /// Loads a text file of UVCIs, one per line.
+    pub fn load_plain_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), RevocationError> {
+        let file = File::open(path).map_err(RevocationError::FileError)?;
+
+        self.load_plain(BufReader::new(file))
+    }
+
+    pub fn load_plain<R: BufRead>(&mut self, r: R) -> Result<(), RevocationError> {
+        let mut count = 0;
+
+        for line in r.lines() {
+            let line = line.map_err(RevocationError::FileError)?;
+
+            for uvci in entries(&line) {
+                self.uvcis.insert(uvci.to_owned());
+                count += 1;
+            }
+        }
+
+        log::debug!(target:"revocation", "Loaded {} plain blocklist entries.", count);
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len() + self.uvcis.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn check(&self, uvci: &str) -> BlocklistStatus {
+        let uvci = uvci.trim();
+
+        if self.uvcis.contains(uvci) {
+            return BlocklistStatus::Listed(BlocklistEntry::Plain);
+        }
+
+        let hash: UvciHash = Sha256::digest(uvci.as_bytes()).into();
+
+        if self.hashes.contains(&hash) {
+            return BlocklistStatus::Listed(BlocklistEntry::Hashed);
+        }
+
+        BlocklistStatus::NotListed
+    }
+}
+
+/// Non empty lines, skipping `#` comments.
+fn entries(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn parse_hash(hex: &str) -> Result<UvciHash, RevocationError> {
+    let invalid = || RevocationError::InvalidHash(hex.to_owned());
+
+    let hex = hex.trim();
+
+    // `from_str_radix` would also accept a sign, as in "+f".
+    if hex.len() != 64 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let mut hash = [0; 32];
+
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+    }
+
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UVCI: &str = "URN:UVCI:01:FR:W7V2BE46QSBJ#L";
+    const UVCI_HASH: &str = "14be9fdca158f8dad4af8767d58ea8aa3efa85698b91a76b8d16c1e76be3cd97";
+
+    fn hashed(content: &str) -> Blocklist {
+        let mut blocklist = Blocklist::new();
+        blocklist.load_hashed(content.as_bytes()).unwrap();
+        blocklist
+    }
+
+    #[test]
+    fn loads_every_hashed_format() {
+        let elements = format!(r#"{{"elements": [{{"hash": "{}"}}]}}"#, UVCI_HASH);
+        let array = format!(r#"["{}"]"#, UVCI_HASH);
+        let text = format!("# hashes\n\n{}\n", UVCI_HASH);
+
+        for content in [elements, array, text] {
+            let blocklist = hashed(&content);
+
+            assert_eq!(blocklist.len(), 1);
+            assert_eq!(
+                blocklist.check(UVCI),
+                BlocklistStatus::Listed(BlocklistEntry::Hashed)
+            );
+            assert_eq!(
+                blocklist.check("URN:UVCI:01:FR:OTHER"),
+                BlocklistStatus::NotListed
+            );
+        }
+    }
+
+    #[test]
+    fn matches_plain_identifiers() {
+        let mut blocklist = Blocklist::new();
+        blocklist
+            .load_plain(format!("# fraudulent\n  {}  \n", UVCI).as_bytes())
+            .unwrap();
+
+        assert_eq!(
+            blocklist.check(UVCI),
+            BlocklistStatus::Listed(BlocklistEntry::Plain)
+        );
+        assert_eq!(
+            blocklist.check("URN:UVCI:01:FR:OTHER"),
+            BlocklistStatus::NotListed
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_hashes() {
+        let mut blocklist = Blocklist::new();
+
+        assert!(blocklist.load_hashed(&b"not a hash"[..]).is_err());
+        assert!(blocklist.load_hashed(&UVCI_HASH.as_bytes()[1..]).is_err());
+
+        let signed = format!("+{}", &UVCI_HASH[1..]);
+        assert!(blocklist.load_hashed(signed.as_bytes()).is_err());
+    }
+}
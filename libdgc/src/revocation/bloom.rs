@@ -0,0 +1,151 @@
+use sha2::{Digest, Sha256};
+
+/// A Bloom filter chunk, in the binary format of the DCC revocation
+/// distribution service. Every field is big-endian:
+///
+/// ```text
+/// u16         version
+/// i32         number of hash functions (k)
+/// f32         false positive probability
+/// i32         defined element amount
+/// i32         current element amount
+/// i32         data length, in 32 bit words (n)
+/// n * i32     bit array
+/// ```
+///
+/// Bit `i` of the filter is bit `i % 32` of word `i / 32`. Hash function `j`
+/// is SHA-256 of the element followed by `j` as a single byte, so at most 255
+/// hash functions are supported.
+pub struct BloomFilter {
+    hash_count: u8,
+    false_positive_rate: f32,
+    data: Vec<u32>,
+}
+
+const HEADER_LEN: usize = 2 + 4 + 4 + 4 + 4 + 4;
+
+impl BloomFilter {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < HEADER_LEN {
+            return Err("Truncated Bloom filter header.");
+        }
+
+        let word =
+            |offset: usize| u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap());
+
+        // The index of each hash function is hashed as a single byte.
+        let hash_count = match u8::try_from(word(2)) {
+            Ok(hash_count) if hash_count > 0 => hash_count,
+            _ => return Err("Unsupported number of Bloom filter hash functions."),
+        };
+
+        let false_positive_rate = f32::from_bits(word(6));
+        let data_len = word(18) as usize;
+
+        let data_bytes = &bytes[HEADER_LEN..];
+
+        if data_len == 0 || data_bytes.len() != data_len * 4 {
+            return Err("Bloom filter length does not match its header.");
+        }
+
+        let data = data_bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        Ok(BloomFilter {
+            hash_count,
+            false_positive_rate,
+            data,
+        })
+    }
+
+    pub fn false_positive_rate(&self) -> f32 {
+        self.false_positive_rate
+    }
+
+    /// Bloom filters have no false negatives: `false` means `element` was
+    /// never added, `true` that it probably was.
+    pub fn might_contain(&self, element: &[u8]) -> bool {
+        let bit_count = self.data.len() as u64 * 32;
+
+        (0..self.hash_count).all(|i| {
+            let index = bit_index(element, i, bit_count);
+
+            self.data[(index / 32) as usize] & (1 << (index % 32)) != 0
+        })
+    }
+}
+
+/// SHA-256 of `element` followed by the hash function index, read as a
+/// big-endian integer modulo `bit_count`.
+fn bit_index(element: &[u8], hash_index: u8, bit_count: u64) -> u64 {
+    let digest = Sha256::new()
+        .chain_update(element)
+        .chain_update([hash_index])
+        .finalize();
+
+    digest
+        .iter()
+        .fold(0, |acc, byte| (acc * 256 + *byte as u64) % bit_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::revocation::RevocationHash;
+
+    /// Filter of the country code and UVCI hash of
+    /// `URN:UVCI:01:FR:W7V2BE46QSBJ#L` and 5 other hashes, with 7 hash
+    /// functions over 128 bits.
+    const FILTER: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/revocation/1112131415161718/5/7.bloom"
+    ));
+
+    const MEMBER: RevocationHash = [
+        0x57, 0x56, 0x60, 0xf8, 0xfb, 0xb0, 0xb7, 0x22, 0xed, 0x31, 0x40, 0x9f, 0xc8, 0x65, 0x7f,
+        0x9f,
+    ];
+
+    fn with_hash_count(hash_count: u32) -> Vec<u8> {
+        let mut bytes = FILTER.to_vec();
+        bytes[2..6].copy_from_slice(&hash_count.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn reads_reference_filter() {
+        let filter = BloomFilter::from_bytes(FILTER).unwrap();
+
+        assert_eq!(filter.hash_count, 7);
+        assert_eq!(filter.data.len(), 4);
+        assert!((filter.false_positive_rate() - 0.01).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn contains_added_elements_only() {
+        let filter = BloomFilter::from_bytes(FILTER).unwrap();
+
+        assert!(filter.might_contain(&MEMBER));
+
+        let mut other = MEMBER;
+        other[15] ^= 0x01;
+
+        assert!(!filter.might_contain(&other));
+        assert!(!filter.might_contain(b"absent"));
+    }
+
+    #[test]
+    fn rejects_unsupported_hash_counts() {
+        assert!(BloomFilter::from_bytes(&with_hash_count(0)).is_err());
+        assert!(BloomFilter::from_bytes(&with_hash_count(255)).is_ok());
+        assert!(BloomFilter::from_bytes(&with_hash_count(256)).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_filters() {
+        assert!(BloomFilter::from_bytes(&FILTER[..HEADER_LEN - 1]).is_err());
+        assert!(BloomFilter::from_bytes(&FILTER[..FILTER.len() - 4]).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use super::{
+    bloom::BloomFilter, HashType, RevocationError, RevocationHash, RevocationHashes, HASH_LEN,
+};
+
+const BLOOM_FILTER_EXT: &str = "bloom";
+const HASH_LIST_EXT: &str = "list";
+
+/// Index of the revocation lists, as served by `/lists`.
+const LISTS_FILE: &str = "lists.json";
+
+/// How the revocation list of a key is split, based on the leading hex
+/// digits of the hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PartitionMode {
+    /// A single partition; chunks keyed by the 1st hex digit.
+    Point,
+
+    /// Partitions keyed by the 1st hex digit, chunks by the 2nd.
+    Vector,
+
+    /// Partitions keyed by the first 2 hex digits, chunks by the 3rd.
+    Coordinate,
+}
+
+impl PartitionMode {
+    /// Partition and chunk ids holding `hash`.
+    fn locate(self, hash: &RevocationHash) -> (String, char) {
+        let hex: String = hash[..2].iter().map(|b| format!("{:02x}", b)).collect();
+        let digit = |i: usize| hex.as_bytes()[i] as char;
+
+        match self {
+            PartitionMode::Point => ("null".to_owned(), digit(0)),
+            PartitionMode::Vector => (hex[..1].to_owned(), digit(1)),
+            PartitionMode::Coordinate => (hex[..2].to_owned(), digit(2)),
+        }
+    }
+}
+
+/// Revocation list of a key, as described by the DCC revocation API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RevocationList {
+    /// Base64 encoded, as found in certificates
+    kid: String,
+    mode: PartitionMode,
+    hash_types: Vec<HashType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChunkMatch {
+    NotFound,
+
+    /// Found in a sorted hash list.
+    Present(HashType),
+
+    /// Found in a Bloom filter, which may be a false positive.
+    ProbablyPresent {
+        hash_type: HashType,
+        false_positive_rate: f32,
+    },
+}
+
+/// Revocation chunks stored on disk, one directory per key, along with the
+/// response of `/lists` describing every list:
+///
+/// ```text
+/// <root>/lists.json
+/// <root>/<kid, hex>/<partition id>/<chunk id>.bloom
+/// <root>/<kid, hex>/<partition id>/<chunk id>.list
+/// ```
+///
+/// The partition id is `null` in point mode, and the leading hex digit(s) of
+/// the hashes otherwise. Chunks are only read when a certificate signed with
+/// their key is looked up, so memory usage is bounded by the size of a chunk.
+pub struct ChunkStore {
+    root: PathBuf,
+
+    /// Lists by key id
+    lists: HashMap<String, RevocationList>,
+}
+
+impl ChunkStore {
+    /// Reads the index of the lists found in `root`.
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<Self, RevocationError> {
+        let root = root.as_ref().to_path_buf();
+
+        let file = File::open(root.join(LISTS_FILE)).map_err(RevocationError::FileError)?;
+
+        let lists: Vec<RevocationList> =
+            serde_json::from_reader(BufReader::new(file)).map_err(RevocationError::ParsingError)?;
+
+        log::debug!(target:"revocation", "Found {} revocation lists in: {}", lists.len(), root.display());
+
+        let lists = lists
+            .into_iter()
+            .map(|list| (list.kid.clone(), list))
+            .collect();
+
+        Ok(ChunkStore { root, lists })
+    }
+
+    /// How the revocation list of `kid` is partitioned, if there is one.
+    pub fn partition_mode(&self, kid: &str) -> Option<PartitionMode> {
+        self.lists.get(kid).map(|list| list.mode)
+    }
+
+    /// `kid` is base64 encoded, as found in certificates.
+    pub fn lookup(
+        &self,
+        kid: &str,
+        hashes: &RevocationHashes,
+    ) -> Result<ChunkMatch, RevocationError> {
+        let list = match self.lists.get(kid) {
+            Some(list) => list,
+            None => return Ok(ChunkMatch::NotFound),
+        };
+
+        let kid_dir = match base64::decode(kid) {
+            Ok(kid) => self.root.join(hex(&kid)),
+            Err(_) => return Ok(ChunkMatch::NotFound),
+        };
+
+        log::debug!(target:"revocation", "Revocation chunks for key {} in {:?} mode.", kid, list.mode);
+
+        for hash_type in [HashType::Uci, HashType::CountryCodeUci, HashType::Signature] {
+            if !list.hash_types.contains(&hash_type) {
+                continue;
+            }
+
+            let hash = hashes.get(hash_type);
+            let (partition, chunk) = list.mode.locate(hash);
+
+            let chunk_path = kid_dir.join(partition).join(chunk.to_string());
+
+            if let Some(bytes) = read_chunk(&chunk_path.with_extension(HASH_LIST_EXT))? {
+                if contains_hash(&bytes, hash)? {
+                    return Ok(ChunkMatch::Present(hash_type));
+                }
+            }
+
+            if let Some(bytes) = read_chunk(&chunk_path.with_extension(BLOOM_FILTER_EXT))? {
+                let filter = BloomFilter::from_bytes(&bytes)
+                    .map_err(|e| RevocationError::InvalidChunk(e.to_owned()))?;
+
+                if filter.might_contain(hash) {
+                    return Ok(ChunkMatch::ProbablyPresent {
+                        hash_type,
+                        false_positive_rate: filter.false_positive_rate(),
+                    });
+                }
+            }
+        }
+
+        Ok(ChunkMatch::NotFound)
+    }
+}
+
+fn read_chunk(path: &Path) -> Result<Option<Vec<u8>>, RevocationError> {
+    match fs::read(path) {
+        Ok(bytes) => {
+            log::trace!(target:"revocation", "Read chunk: {}", path.display());
+
+            Ok(Some(bytes))
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(RevocationError::FileError(e)),
+    }
+}
+
+/// Binary search in a sorted list of concatenated hashes.
+fn contains_hash(list: &[u8], hash: &RevocationHash) -> Result<bool, RevocationError> {
+    let chunks = list.chunks_exact(HASH_LEN);
+
+    if !chunks.remainder().is_empty() {
+        return Err(RevocationError::InvalidChunk(
+            "Hash list length is not a multiple of the hash length.".to_owned(),
+        ));
+    }
+
+    let hashes: Vec<&[u8]> = chunks.collect();
+
+    Ok(hashes.binary_search(&&hash[..]).is_ok())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lists of three keys, one per partition mode, each holding one hash of
+    /// `URN:UVCI:01:FR:W7V2BE46QSBJ#L` besides other hashes.
+    fn store() -> ChunkStore {
+        ChunkStore::open(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/revocation")).unwrap()
+    }
+
+    fn hashes() -> RevocationHashes {
+        let r: Vec<u8> = (1..=32).collect();
+
+        RevocationHashes::compute("URN:UVCI:01:FR:W7V2BE46QSBJ#L", "FR", &r)
+    }
+
+    #[test]
+    fn reads_partition_modes_from_lists() {
+        let store = store();
+
+        assert_eq!(
+            store.partition_mode("AQIDBAUGBwg="),
+            Some(PartitionMode::Point)
+        );
+        assert_eq!(
+            store.partition_mode("ERITFBUWFxg="),
+            Some(PartitionMode::Vector)
+        );
+        assert_eq!(
+            store.partition_mode("ISIjJCUmJyg="),
+            Some(PartitionMode::Coordinate)
+        );
+        assert_eq!(store.partition_mode("AAAAAAAAAAA="), None);
+    }
+
+    #[test]
+    fn locates_chunks() {
+        let hash = [0xae, 0x21, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+        assert_eq!(PartitionMode::Point.locate(&hash), ("null".into(), 'a'));
+        assert_eq!(PartitionMode::Vector.locate(&hash), ("a".into(), 'e'));
+        assert_eq!(PartitionMode::Coordinate.locate(&hash), ("ae".into(), '2'));
+    }
+
+    #[test]
+    fn finds_hashes_in_lists() {
+        let store = store();
+
+        assert_eq!(
+            store.lookup("AQIDBAUGBwg=", &hashes()).unwrap(),
+            ChunkMatch::Present(HashType::Uci)
+        );
+        assert_eq!(
+            store.lookup("ISIjJCUmJyg=", &hashes()).unwrap(),
+            ChunkMatch::Present(HashType::Signature)
+        );
+    }
+
+    #[test]
+    fn finds_hashes_in_bloom_filters() {
+        assert!(matches!(
+            store().lookup("ERITFBUWFxg=", &hashes()).unwrap(),
+            ChunkMatch::ProbablyPresent {
+                hash_type: HashType::CountryCodeUci,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn ignores_other_certificates_and_keys() {
+        let store = store();
+
+        let r: Vec<u8> = (2..=33).collect();
+        let other = RevocationHashes::compute("URN:UVCI:01:FR:OTHER", "FR", &r);
+
+        for kid in ["AQIDBAUGBwg=", "ERITFBUWFxg=", "ISIjJCUmJyg="] {
+            assert_eq!(store.lookup(kid, &other).unwrap(), ChunkMatch::NotFound);
+        }
+
+        assert_eq!(
+            store.lookup("AAAAAAAAAAA=", &hashes()).unwrap(),
+            ChunkMatch::NotFound
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_hash_lists() {
+        assert!(contains_hash(&[0; HASH_LEN + 1], &[0; HASH_LEN]).is_err());
+        assert!(contains_hash(&[0; 2 * HASH_LEN], &[0; HASH_LEN]).unwrap());
+    }
+}
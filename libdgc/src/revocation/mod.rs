@@ -0,0 +1,351 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+mod blocklist;
+mod bloom;
+mod chunks;
+
+pub use blocklist::{Blocklist, BlocklistEntry, BlocklistStatus};
+pub use bloom::BloomFilter;
+pub use chunks::{ChunkMatch, ChunkStore, PartitionMode};
+
+/// Revocation hashes are SHA-256 digests truncated to their first 128 bits.
+pub const HASH_LEN: usize = 16;
+
+pub type RevocationHash = [u8; HASH_LEN];
+
+#[derive(Debug)]
+pub enum RevocationError {
+    FileError(std::io::Error),
+    ParsingError(serde_json::Error),
+    InvalidHash(String),
+    InvalidChunk(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum HashType {
+    /// Hash of the UVCI
+    #[serde(rename = "UCI")]
+    Uci,
+
+    /// Hash of the issuing country code followed by the UVCI
+    #[serde(rename = "COUNTRYCODEUCI")]
+    CountryCodeUci,
+
+    /// Hash of the COSE signature (only `r` for ECDSA signatures)
+    #[serde(rename = "SIGNATURE")]
+    Signature,
+}
+
+/// The three revocation hashes of a single certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevocationHashes {
+    pub uci: RevocationHash,
+    pub country_code_uci: RevocationHash,
+    pub signature: RevocationHash,
+}
+
+impl RevocationHashes {
+    /// `signature` is the COSE signature, or only its `r` component for
+    /// ECDSA signatures, in the size of the curve as in the `r || s`
+    /// encoding.
+    pub fn compute(uvci: &str, country: &str, signature: &[u8]) -> Self {
+        RevocationHashes {
+            uci: truncated_sha256(&[uvci.as_bytes()]),
+            country_code_uci: truncated_sha256(&[country.as_bytes(), uvci.as_bytes()]),
+            signature: truncated_sha256(&[signature]),
+        }
+    }
+
+    fn get(&self, hash_type: HashType) -> &RevocationHash {
+        match hash_type {
+            HashType::Uci => &self.uci,
+            HashType::CountryCodeUci => &self.country_code_uci,
+            HashType::Signature => &self.signature,
+        }
+    }
+}
+
+fn truncated_sha256(parts: &[&[u8]]) -> RevocationHash {
+    let mut hasher = Sha256::new();
+
+    for part in parts {
+        hasher.update(part);
+    }
+
+    let mut hash = [0; HASH_LEN];
+    hash.copy_from_slice(&hasher.finalize()[..HASH_LEN]);
+
+    hash
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RevocationStatus {
+    NotRevoked,
+    Revoked {
+        batch_id: String,
+        hash_type: HashType,
+    },
+}
+
+/// Where a revoked certificate was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Revocation {
+    /// In a revocation batch
+    Batch {
+        batch_id: String,
+        hash_type: HashType,
+    },
+
+    /// In a sorted hash list of revocation chunks
+    Chunk(HashType),
+
+    /// In a Bloom filter of revocation chunks, which may be a false positive.
+    ProbableChunk {
+        hash_type: HashType,
+        false_positive_rate: f32,
+    },
+}
+
+/// A revocation batch, as distributed by the DCC gateway.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Batch {
+    country: String,
+    expires: DateTime<Utc>,
+    kid: String,
+    hash_type: HashType,
+    entries: Vec<BatchEntry>,
+}
+
+#[derive(Deserialize)]
+struct BatchEntry {
+    hash: String,
+}
+
+struct BatchInfo {
+    id: String,
+    country: String,
+    kid: String,
+    expires: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct RevocationStore {
+    batches: Vec<BatchInfo>,
+    entries: HashMap<(HashType, RevocationHash), Vec<usize>>,
+}
+
+impl RevocationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads every `<batch id>.json` file found in `dir`.
+    pub fn load_from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, RevocationError> {
+        use RevocationError::FileError;
+
+        log::debug!(target:"revocation", "Loading revocation batches from: {}", dir.as_ref().display());
+
+        let mut store = Self::new();
+
+        for entry in fs::read_dir(dir).map_err(FileError)? {
+            let path = entry.map_err(FileError)?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                store.load_batch_file(&path)?;
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Loads a single batch, using the file name as the batch id.
+    pub fn load_batch_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), RevocationError> {
+        let path = path.as_ref();
+
+        let batch_id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let file = File::open(path).map_err(RevocationError::FileError)?;
+
+        self.load_batch(batch_id, BufReader::new(file))
+    }
+
+    pub fn load_batch<R: Read>(&mut self, batch_id: String, r: R) -> Result<(), RevocationError> {
+        let batch: Batch = serde_json::from_reader(r).map_err(RevocationError::ParsingError)?;
+
+        let index = self.batches.len();
+
+        for entry in &batch.entries {
+            let decoded = base64::decode(&entry.hash)
+                .map_err(|e| RevocationError::InvalidHash(e.to_string()))?;
+
+            if decoded.len() < HASH_LEN {
+                return Err(RevocationError::InvalidHash(entry.hash.clone()));
+            }
+
+            let mut hash = [0; HASH_LEN];
+            hash.copy_from_slice(&decoded[..HASH_LEN]);
+
+            self.entries
+                .entry((batch.hash_type, hash))
+                .or_default()
+                .push(index);
+        }
+
+        log::debug!(target:"revocation", "Loaded batch '{}' ({}, {:?}): {} entries", batch_id, batch.country, batch.hash_type, batch.entries.len());
+
+        self.batches.push(BatchInfo {
+            id: batch_id,
+            country: batch.country,
+            kid: batch.kid,
+            expires: batch.expires,
+        });
+
+        Ok(())
+    }
+
+    /// Looks `hashes` up in every batch issued for `kid` that has not expired
+    /// at `at`.
+    pub fn check(
+        &self,
+        hashes: &RevocationHashes,
+        kid: &str,
+        at: DateTime<Utc>,
+    ) -> RevocationStatus {
+        for hash_type in [HashType::Uci, HashType::CountryCodeUci, HashType::Signature] {
+            let batches = match self.entries.get(&(hash_type, *hashes.get(hash_type))) {
+                Some(batches) => batches,
+                None => continue,
+            };
+
+            let matching = batches
+                .iter()
+                .map(|index| &self.batches[*index])
+                .find(|batch| batch.kid == kid && batch.expires > at);
+
+            if let Some(batch) = matching {
+                log::info!(target:"revocation", "Certificate revoked by batch '{}' from {}.", batch.id, batch.country);
+
+                return RevocationStatus::Revoked {
+                    batch_id: batch.id.clone(),
+                    hash_type,
+                };
+            }
+        }
+
+        RevocationStatus::NotRevoked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UVCI: &str = "URN:UVCI:01:FR:W7V2BE46QSBJ#L";
+
+    /// `r` of an ES256 signature, 32 bytes long
+    fn ecdsa_r() -> Vec<u8> {
+        (1..=32).collect()
+    }
+
+    fn hashes() -> RevocationHashes {
+        RevocationHashes::compute(UVCI, "FR", &ecdsa_r())
+    }
+
+    #[test]
+    fn computes_truncated_sha256() {
+        let hashes = hashes();
+
+        assert_eq!(
+            hashes.uci,
+            [
+                0x14, 0xbe, 0x9f, 0xdc, 0xa1, 0x58, 0xf8, 0xda, 0xd4, 0xaf, 0x87, 0x67, 0xd5, 0x8e,
+                0xa8, 0xaa
+            ]
+        );
+        assert_eq!(
+            hashes.country_code_uci,
+            [
+                0x57, 0x56, 0x60, 0xf8, 0xfb, 0xb0, 0xb7, 0x22, 0xed, 0x31, 0x40, 0x9f, 0xc8, 0x65,
+                0x7f, 0x9f
+            ]
+        );
+        assert_eq!(
+            hashes.signature,
+            [
+                0xae, 0x21, 0x6c, 0x2e, 0xf5, 0x24, 0x7a, 0x37, 0x82, 0xc1, 0x35, 0xef, 0xa2, 0x79,
+                0xa3, 0xe4
+            ]
+        );
+    }
+
+    fn store(hash_type: &str, hash: &str) -> RevocationStore {
+        let batch = format!(
+            r#"{{
+                "country": "FR",
+                "expires": "2022-07-07T00:00:00Z",
+                "kid": "kid",
+                "hashType": "{}",
+                "entries": [{{ "hash": "{}" }}]
+            }}"#,
+            hash_type, hash
+        );
+
+        let mut store = RevocationStore::new();
+        store.load_batch("batch".into(), batch.as_bytes()).unwrap();
+        store
+    }
+
+    #[test]
+    fn finds_every_hash_type() {
+        let before_expiry = "2022-07-01T00:00:00Z".parse().unwrap();
+
+        for (hash_type, expected, hash) in [
+            ("UCI", HashType::Uci, "FL6f3KFY+NrUr4dn1Y6oqg=="),
+            (
+                "COUNTRYCODEUCI",
+                HashType::CountryCodeUci,
+                "V1Zg+PuwtyLtMUCfyGV/nw==",
+            ),
+            ("SIGNATURE", HashType::Signature, "riFsLvUkejeCwTXvonmj5A=="),
+        ] {
+            let store = store(hash_type, hash);
+
+            assert_eq!(
+                store.check(&hashes(), "kid", before_expiry),
+                RevocationStatus::Revoked {
+                    batch_id: "batch".into(),
+                    hash_type: expected,
+                }
+            );
+            assert_eq!(
+                store.check(&hashes(), "other kid", before_expiry),
+                RevocationStatus::NotRevoked
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_expired_batches() {
+        let store = store("UCI", "FL6f3KFY+NrUr4dn1Y6oqg==");
+
+        let after_expiry = "2022-07-08T00:00:00Z".parse().unwrap();
+
+        assert_eq!(
+            store.check(&hashes(), "kid", after_expiry),
+            RevocationStatus::NotRevoked
+        );
+    }
+}
@@ -0,0 +1,777 @@
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate};
+use regex::Regex;
+use serde_cbor::Value as CborValue;
+use serde_json::{Map, Value};
+
+use crate::date::PartialDate;
+
+/// Schema versions this validator knows the rules of.
+const LATEST_VERSION: (u32, u32) = (1, 3);
+
+const MAX_LENGTH: usize = 80;
+
+const COUNTRY_PATTERN: &str = "^[A-Z]{2}$";
+const STANDARDIZED_NAME_PATTERN: &str = "^[A-Z<]*$";
+const VERSION_PATTERN: &str = r"^\d+\.\d+\.\d+$";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    Missing,
+    WrongType {
+        expected: &'static str,
+    },
+    TooLong {
+        max: usize,
+    },
+    PatternMismatch {
+        pattern: &'static str,
+    },
+    InvalidFormat {
+        format: &'static str,
+    },
+    OutOfRange {
+        min: i64,
+        max: i64,
+    },
+
+    /// Exactly one of `v`, `t` or `r` must be present.
+    NotOneOf,
+
+    /// Each of `v`, `t`, `r` or `e` must hold exactly one entry.
+    ItemCount,
+
+    /// The rules of the latest known version were applied instead.
+    UnsupportedVersion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON pointer to the offending value
+    pub path: String,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ViolationKind::*;
+
+        let path = if self.path.is_empty() {
+            "<root>"
+        } else {
+            &self.path
+        };
+
+        match &self.kind {
+            Missing => write!(f, "{}: required field is missing", path),
+            WrongType { expected } => write!(f, "{}: expected {}", path, expected),
+            TooLong { max } => write!(f, "{}: longer than {} characters", path, max),
+            PatternMismatch { pattern } => write!(f, "{}: does not match {}", path, pattern),
+            InvalidFormat { format } => write!(f, "{}: not a valid {}", path, format),
+            OutOfRange { min, max } => write!(f, "{}: not between {} and {}", path, min, max),
+            NotOneOf => write!(f, "{}: expected exactly one of v, t or r", path),
+            ItemCount => write!(f, "{}: expected exactly one entry", path),
+            UnsupportedVersion => write!(f, "{}: unsupported schema version", path),
+        }
+    }
+}
+
+/// Validates certificates, as JSON, against the EU DCC schema matching
+/// their `ver` field (1.0.0 to 1.3.x).
+pub struct SchemaValidator {
+    country: Regex,
+    standardized_name: Regex,
+    version: Regex,
+}
+
+impl Default for SchemaValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SchemaValidator {
+    pub fn new() -> Self {
+        SchemaValidator {
+            country: Regex::new(COUNTRY_PATTERN).unwrap(),
+            standardized_name: Regex::new(STANDARDIZED_NAME_PATTERN).unwrap(),
+            version: Regex::new(VERSION_PATTERN).unwrap(),
+        }
+    }
+
+    /// `dcc` is the certificate found under `hcert`/`1`, paths being
+    /// relative to it.
+    pub fn validate(&self, dcc: &Value) -> Vec<Violation> {
+        let mut check = Check {
+            validator: self,
+            version: LATEST_VERSION,
+            violations: vec![],
+        };
+
+        check.certificate(dcc);
+
+        check.violations
+    }
+
+    /// Validates a certificate still in its CBOR form, so that fields the
+    /// typed model would reject are reported too.
+    pub fn validate_cbor(&self, dcc: &CborValue) -> Vec<Violation> {
+        self.validate(&cbor_to_json(dcc))
+    }
+}
+
+struct Check<'v> {
+    validator: &'v SchemaValidator,
+    version: (u32, u32),
+    violations: Vec<Violation>,
+}
+
+impl Check<'_> {
+    fn violation(&mut self, path: &str, kind: ViolationKind) {
+        self.violations.push(Violation {
+            path: path.to_owned(),
+            kind,
+        });
+    }
+
+    fn certificate(&mut self, dcc: &Value) {
+        let dcc = match self.object("", Some(dcc)) {
+            Some(dcc) => dcc,
+            None => return,
+        };
+
+        if let Some(ver) = self.string("/ver", dcc.get("ver"), true) {
+            if self.validator.version.is_match(ver) {
+                self.version = self.parse_version(ver);
+            } else {
+                self.violation(
+                    "/ver",
+                    ViolationKind::PatternMismatch {
+                        pattern: VERSION_PATTERN,
+                    },
+                );
+            }
+        }
+
+        self.name(dcc.get("nam"));
+
+        if let Some(dob) = self.string("/dob", dcc.get("dob"), true) {
+            self.date_of_birth(dob);
+        }
+
+        let entries: Vec<&str> = ["v", "t", "r"]
+            .into_iter()
+            .filter(|key| dcc.contains_key(*key))
+            .collect();
+
+        if entries.len() != 1 {
+            self.violation("", ViolationKind::NotOneOf);
+        }
+
+        // Exemptions are national, outside of the EU one-of, but still
+        // checked when present.
+        let exemption = Some("e").filter(|key| dcc.contains_key(*key));
+
+        for key in entries.into_iter().chain(exemption) {
+            let path = format!("/{}", key);
+
+            let entry = match dcc.get(key) {
+                Some(Value::Array(items)) if items.len() == 1 => &items[0],
+                Some(Value::Array(_)) => {
+                    self.violation(&path, ViolationKind::ItemCount);
+                    continue;
+                }
+                _ => {
+                    self.violation(&path, ViolationKind::WrongType { expected: "array" });
+                    continue;
+                }
+            };
+
+            let path = format!("{}/0", path);
+
+            match key {
+                "v" => self.vaccination(&path, entry),
+                "t" => self.test(&path, entry),
+                "r" => self.recovery(&path, entry),
+                _ => self.exemption(&path, entry),
+            }
+        }
+    }
+
+    fn parse_version(&mut self, ver: &str) -> (u32, u32) {
+        let mut numbers = ver.split('.').map(|n| n.parse().unwrap_or(0));
+
+        let version = (numbers.next().unwrap_or(0), numbers.next().unwrap_or(0));
+
+        if version.0 != 1 || version > LATEST_VERSION {
+            self.violation("/ver", ViolationKind::UnsupportedVersion);
+
+            return LATEST_VERSION;
+        }
+
+        version
+    }
+
+    fn date_of_birth(&mut self, dob: &str) {
+        // No schema version allows the `XX` placeholders the parser accepts.
+        let valid = match dob.parse::<PartialDate>() {
+            Ok(_) if dob.contains('X') => false,
+            // Empty dates of birth were introduced in 1.3.0
+            Ok(PartialDate::Unknown) => self.version >= (1, 3),
+            // Partial ones in 1.2.0
+            Ok(PartialDate::Date(_)) => true,
+            Ok(_) => self.version >= (1, 2),
+            Err(_) => false,
+        };
+
+        if !valid {
+            self.violation("/dob", ViolationKind::InvalidFormat { format: "date" });
+        }
+    }
+
+    fn name(&mut self, nam: Option<&Value>) {
+        let nam = match self.object("/nam", nam) {
+            Some(nam) => nam,
+            None => return,
+        };
+
+        for field in ["fn", "gn"] {
+            self.text(&format!("/nam/{}", field), nam.get(field), false);
+        }
+
+        for (field, required) in [("fnt", true), ("gnt", false)] {
+            let path = format!("/nam/{}", field);
+
+            if let Some(name) = self.text(&path, nam.get(field), required) {
+                if !self.validator.standardized_name.is_match(name) {
+                    self.violation(
+                        &path,
+                        ViolationKind::PatternMismatch {
+                            pattern: STANDARDIZED_NAME_PATTERN,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    fn vaccination(&mut self, path: &str, entry: &Value) {
+        let entry = match self.object(path, Some(entry)) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        self.common(path, entry);
+
+        for field in ["vp", "mp", "ma"] {
+            self.string(&format!("{}/{}", path, field), entry.get(field), true);
+        }
+
+        for field in ["dn", "sd"] {
+            self.integer(&format!("{}/{}", path, field), entry.get(field), 1, 9);
+        }
+
+        self.date(&format!("{}/dt", path), entry.get("dt"), true);
+    }
+
+    fn test(&mut self, path: &str, entry: &Value) {
+        let entry = match self.object(path, Some(entry)) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        self.common(path, entry);
+
+        self.string(&format!("{}/tt", path), entry.get("tt"), true);
+        self.string(&format!("{}/tr", path), entry.get("tr"), true);
+
+        self.text(&format!("{}/nm", path), entry.get("nm"), false);
+        self.string(&format!("{}/ma", path), entry.get("ma"), false);
+
+        // The testing centre became optional in 1.3.0
+        let tc_required = self.version < (1, 3);
+        self.text(&format!("{}/tc", path), entry.get("tc"), tc_required);
+
+        self.datetime(&format!("{}/sc", path), entry.get("sc"), true);
+
+        // The date of the test result was dropped after 1.0.x
+        let dr_required = self.version < (1, 1);
+        self.datetime(&format!("{}/dr", path), entry.get("dr"), dr_required);
+    }
+
+    fn recovery(&mut self, path: &str, entry: &Value) {
+        let entry = match self.object(path, Some(entry)) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        self.common(path, entry);
+
+        for field in ["fr", "df", "du"] {
+            self.date(&format!("{}/{}", path, field), entry.get(field), true);
+        }
+    }
+
+    /// Vaccination exemptions are not part of the EU schema, their fields
+    /// are those of the typed model.
+    fn exemption(&mut self, path: &str, entry: &Value) {
+        let entry = match self.object(path, Some(entry)) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        self.common(path, entry);
+
+        for field in ["df", "du"] {
+            self.date(&format!("{}/{}", path, field), entry.get(field), true);
+        }
+    }
+
+    /// Fields shared by vaccination, test, recovery and exemption entries.
+    fn common(&mut self, path: &str, entry: &Map<String, Value>) {
+        self.string(&format!("{}/tg", path), entry.get("tg"), true);
+
+        let co = format!("{}/co", path);
+
+        if let Some(country) = self.string(&co, entry.get("co"), true) {
+            if !self.validator.country.is_match(country) {
+                self.violation(
+                    &co,
+                    ViolationKind::PatternMismatch {
+                        pattern: COUNTRY_PATTERN,
+                    },
+                );
+            }
+        }
+
+        self.text(&format!("{}/is", path), entry.get("is"), true);
+        self.text(&format!("{}/ci", path), entry.get("ci"), true);
+    }
+
+    fn object<'a>(
+        &mut self,
+        path: &str,
+        value: Option<&'a Value>,
+    ) -> Option<&'a Map<String, Value>> {
+        match value {
+            Some(Value::Object(map)) => Some(map),
+            Some(_) => {
+                self.violation(path, ViolationKind::WrongType { expected: "object" });
+                None
+            }
+            None => {
+                self.violation(path, ViolationKind::Missing);
+                None
+            }
+        }
+    }
+
+    fn string<'a>(
+        &mut self,
+        path: &str,
+        value: Option<&'a Value>,
+        required: bool,
+    ) -> Option<&'a str> {
+        match value {
+            Some(Value::String(s)) => Some(s),
+            Some(_) => {
+                self.violation(path, ViolationKind::WrongType { expected: "string" });
+                None
+            }
+            None => {
+                if required {
+                    self.violation(path, ViolationKind::Missing);
+                }
+                None
+            }
+        }
+    }
+
+    /// A free text string, limited in length.
+    fn text<'a>(
+        &mut self,
+        path: &str,
+        value: Option<&'a Value>,
+        required: bool,
+    ) -> Option<&'a str> {
+        let text = self.string(path, value, required)?;
+
+        if text.chars().count() > MAX_LENGTH {
+            self.violation(path, ViolationKind::TooLong { max: MAX_LENGTH });
+        }
+
+        Some(text)
+    }
+
+    fn integer(&mut self, path: &str, value: Option<&Value>, min: i64, max: i64) {
+        match value.map(|value| (value, value.as_i64())) {
+            Some((_, Some(n))) if (min..=max).contains(&n) => {}
+            Some((_, Some(_))) => self.violation(path, ViolationKind::OutOfRange { min, max }),
+            Some(_) => self.violation(
+                path,
+                ViolationKind::WrongType {
+                    expected: "integer",
+                },
+            ),
+            None => self.violation(path, ViolationKind::Missing),
+        }
+    }
+
+    fn date(&mut self, path: &str, value: Option<&Value>, required: bool) {
+        if let Some(date) = self.string(path, value, required) {
+            if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                self.violation(path, ViolationKind::InvalidFormat { format: "date" });
+            }
+        }
+    }
+
+    fn datetime(&mut self, path: &str, value: Option<&Value>, required: bool) {
+        if let Some(datetime) = self.string(path, value, required) {
+            if DateTime::parse_from_rfc3339(datetime).is_err() {
+                self.violation(
+                    path,
+                    ViolationKind::InvalidFormat {
+                        format: "date-time",
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Map keys that are not text are turned into strings, which is enough to
+/// report them as unexpected.
+pub(crate) fn cbor_to_json(value: &CborValue) -> Value {
+    match value {
+        CborValue::Null => Value::Null,
+        CborValue::Bool(b) => Value::Bool(*b),
+        CborValue::Integer(n) => i64::try_from(*n)
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(n.to_string())),
+        CborValue::Float(f) => Value::from(*f),
+        CborValue::Bytes(bytes) => Value::String(base64::encode(bytes)),
+        CborValue::Text(text) => Value::String(text.clone()),
+        CborValue::Array(items) => Value::Array(items.iter().map(cbor_to_json).collect()),
+        CborValue::Map(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        CborValue::Text(text) => text.clone(),
+                        other => format!("{:?}", other),
+                    };
+
+                    (key, cbor_to_json(value))
+                })
+                .collect(),
+        ),
+        CborValue::Tag(_, value) => cbor_to_json(value),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::hcert::CertificateData;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            serde_json::from_str::<Value>(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/schema/",
+                $name,
+                ".json"
+            )))
+            .unwrap()
+        };
+    }
+
+    /// Violations as `(path, kind)`, checking that the paths of values that
+    /// are present point into `dcc`.
+    fn validate(dcc: &Value) -> Vec<(String, ViolationKind)> {
+        let violations = SchemaValidator::new().validate(dcc);
+
+        for violation in &violations {
+            if violation.kind != ViolationKind::Missing {
+                assert!(
+                    dcc.pointer(&violation.path).is_some(),
+                    "{} does not point into the certificate",
+                    violation.path
+                );
+            }
+        }
+
+        violations
+            .into_iter()
+            .map(|violation| (violation.path, violation.kind))
+            .collect()
+    }
+
+    fn missing(path: &str) -> (String, ViolationKind) {
+        (path.to_owned(), ViolationKind::Missing)
+    }
+
+    fn invalid_dob() -> (String, ViolationKind) {
+        (
+            "/dob".to_owned(),
+            ViolationKind::InvalidFormat { format: "date" },
+        )
+    }
+
+    #[test]
+    fn fixtures_are_valid() {
+        assert_eq!(validate(&fixture!("test-1.0.0")), vec![]);
+        assert_eq!(validate(&fixture!("test-1.3.0")), vec![]);
+        assert_eq!(validate(&fixture!("vaccination-1.3.0")), vec![]);
+    }
+
+    #[test]
+    fn fixtures_fit_the_typed_model() {
+        for dcc in [
+            fixture!("test-1.0.0"),
+            fixture!("test-1.3.0"),
+            fixture!("vaccination-1.3.0"),
+        ] {
+            let json = dcc.to_string();
+
+            assert!(serde_json::from_str::<CertificateData>(&json).is_ok());
+        }
+    }
+
+    #[test]
+    fn testing_centre_is_optional_from_1_3() {
+        let mut dcc = fixture!("test-1.3.0");
+        dcc["dob"] = json!("1964-08-12");
+
+        for ver in ["1.1.0", "1.2.1"] {
+            dcc["ver"] = json!(ver);
+
+            assert_eq!(validate(&dcc), vec![missing("/t/0/tc")], "{}", ver);
+        }
+
+        for ver in ["1.3.0", "1.3.2"] {
+            dcc["ver"] = json!(ver);
+
+            assert_eq!(validate(&dcc), vec![], "{}", ver);
+        }
+    }
+
+    #[test]
+    fn result_date_is_only_required_in_1_0() {
+        let mut dcc = fixture!("test-1.0.0");
+        dcc["t"][0].as_object_mut().unwrap().remove("dr");
+
+        for ver in ["1.0.0", "1.0.1"] {
+            dcc["ver"] = json!(ver);
+
+            assert_eq!(validate(&dcc), vec![missing("/t/0/dr")], "{}", ver);
+        }
+
+        for ver in ["1.1.0", "1.3.0"] {
+            dcc["ver"] = json!(ver);
+
+            assert_eq!(validate(&dcc), vec![], "{}", ver);
+        }
+    }
+
+    #[test]
+    fn optional_test_fields_are_still_checked() {
+        let mut dcc = fixture!("test-1.0.0");
+        dcc["ver"] = json!("1.3.0");
+        dcc["t"][0]["dr"] = json!("2021-05-30");
+        dcc["t"][0]["tc"] = json!("x".repeat(81));
+
+        assert_eq!(
+            validate(&dcc),
+            vec![
+                ("/t/0/tc".to_owned(), ViolationKind::TooLong { max: 80 }),
+                (
+                    "/t/0/dr".to_owned(),
+                    ViolationKind::InvalidFormat {
+                        format: "date-time"
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_dates_of_birth_depend_on_the_version() {
+        let mut dcc = fixture!("vaccination-1.3.0");
+
+        for (ver, dob, valid) in [
+            ("1.0.0", "1980-05-01", true),
+            ("1.0.0", "1980-05", false),
+            ("1.0.0", "1980", false),
+            ("1.0.0", "", false),
+            ("1.1.0", "1980-05", false),
+            ("1.2.0", "1980-05", true),
+            ("1.2.1", "1980-05", true),
+            ("1.2.1", "1980", true),
+            ("1.2.1", "", false),
+            ("1.3.0", "1980", true),
+            ("1.3.0", "", true),
+            ("1.3.0", "1980-XX-XX", false),
+            ("1.3.0", "1980-05-32", false),
+        ] {
+            dcc["ver"] = json!(ver);
+            dcc["dob"] = json!(dob);
+
+            let expected = if valid { vec![] } else { vec![invalid_dob()] };
+
+            assert_eq!(validate(&dcc), expected, "{} {:?}", ver, dob);
+        }
+    }
+
+    #[test]
+    fn points_to_the_offending_values() {
+        let mut dcc = fixture!("vaccination-1.3.0");
+        dcc["nam"]["fnt"] = json!("Dupont");
+        dcc["v"][0]["dn"] = json!(10);
+        dcc["v"][0]["co"] = json!("FRA");
+        dcc["v"][0]["dt"] = json!(20210615);
+        dcc["v"][0].as_object_mut().unwrap().remove("ci");
+
+        assert_eq!(
+            validate(&dcc),
+            vec![
+                (
+                    "/nam/fnt".to_owned(),
+                    ViolationKind::PatternMismatch {
+                        pattern: STANDARDIZED_NAME_PATTERN
+                    }
+                ),
+                (
+                    "/v/0/co".to_owned(),
+                    ViolationKind::PatternMismatch {
+                        pattern: COUNTRY_PATTERN
+                    }
+                ),
+                missing("/v/0/ci"),
+                (
+                    "/v/0/dn".to_owned(),
+                    ViolationKind::OutOfRange { min: 1, max: 9 }
+                ),
+                (
+                    "/v/0/dt".to_owned(),
+                    ViolationKind::WrongType { expected: "string" }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn paths_are_json_pointers() {
+        let mut dcc = fixture!("test-1.0.0");
+        dcc["t"][0]["sc"] = json!("yesterday");
+        dcc["t"][0]["co"] = json!("de");
+
+        let violations = SchemaValidator::new().validate(&dcc);
+        let paths: Vec<_> = violations.iter().map(|v| v.path.as_str()).collect();
+
+        assert_eq!(paths, ["/t/0/co", "/t/0/sc"]);
+        assert_eq!(dcc.pointer(paths[0]), Some(&json!("de")));
+        assert_eq!(dcc.pointer(paths[1]), Some(&json!("yesterday")));
+        assert_eq!(violations[1].to_string(), "/t/0/sc: not a valid date-time");
+
+        dcc["r"] = json!([]);
+
+        let violations = SchemaValidator::new().validate(&dcc);
+
+        assert_eq!(
+            violations[0].to_string(),
+            "<root>: expected exactly one of v, t or r"
+        );
+    }
+
+    #[test]
+    fn checks_entry_counts() {
+        let mut dcc = fixture!("vaccination-1.3.0");
+        dcc["t"] = fixture!("test-1.3.0")["t"].clone();
+
+        assert_eq!(
+            validate(&dcc),
+            vec![("".to_owned(), ViolationKind::NotOneOf)]
+        );
+
+        let mut dcc = fixture!("vaccination-1.3.0");
+        let entry = dcc["v"][0].clone();
+        dcc["v"] = json!([entry.clone(), entry]);
+
+        assert_eq!(
+            validate(&dcc),
+            vec![("/v".to_owned(), ViolationKind::ItemCount)]
+        );
+    }
+
+    #[test]
+    fn validates_exemptions() {
+        let mut dcc = fixture!("vaccination-1.3.0");
+        dcc.as_object_mut().unwrap().remove("v");
+        dcc["e"] = json!([{
+            "tg": "840539006",
+            "co": "FR",
+            "is": "CNAM",
+            "df": "2022-01-01",
+            "du": "2022-06",
+            "ci": "URN:UVCI:01:FR:W7V2BE46QSBJ#L"
+        }]);
+
+        assert_eq!(
+            validate(&dcc),
+            vec![
+                ("".to_owned(), ViolationKind::NotOneOf),
+                (
+                    "/e/0/du".to_owned(),
+                    ViolationKind::InvalidFormat { format: "date" }
+                ),
+            ]
+        );
+
+        dcc["e"][0]["du"] = json!("2022-06-30");
+        dcc["v"] = fixture!("vaccination-1.3.0")["v"].clone();
+
+        assert_eq!(validate(&dcc), vec![]);
+    }
+
+    #[test]
+    fn exemptions_alone_are_not_eu_certificates() {
+        let mut dcc = fixture!("vaccination-1.3.0");
+        dcc.as_object_mut().unwrap().remove("v");
+        dcc["e"] = json!([{
+            "tg": "840539006",
+            "co": "FR",
+            "is": "CNAM",
+            "df": "2022-01-01",
+            "du": "2022-06-30",
+            "ci": "URN:UVCI:01:FR:W7V2BE46QSBJ#L"
+        }]);
+
+        assert_eq!(
+            validate(&dcc),
+            vec![("".to_owned(), ViolationKind::NotOneOf)]
+        );
+    }
+
+    #[test]
+    fn unsupported_versions_use_the_latest_rules() {
+        let mut dcc = fixture!("test-1.3.0");
+        dcc["ver"] = json!("1.4.0");
+
+        assert_eq!(
+            validate(&dcc),
+            vec![("/ver".to_owned(), ViolationKind::UnsupportedVersion)]
+        );
+    }
+
+    #[test]
+    fn validates_cbor_certificates() {
+        let dcc = fixture!("test-1.0.0");
+        let cbor = serde_cbor::value::to_value(&dcc).unwrap();
+
+        assert_eq!(SchemaValidator::new().validate_cbor(&cbor), vec![]);
+    }
+}
@@ -0,0 +1,264 @@
+use std::{fmt, str::FromStr};
+
+const PREFIX: &str = "URN:UVCI:";
+
+/// Code points of the Luhn mod N checksum.
+const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ/:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UvciError {
+    InvalidVersion(String),
+    InvalidCountry(String),
+    EmptyIdentifier,
+    InvalidCharacter(char),
+    InvalidChecksum { expected: char, found: char },
+}
+
+/// Unique Vaccination Certificate/assertion Identifier, as found in the `ci`
+/// field: `URN:UVCI:01:NL:187/37512422923`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Uvci {
+    version: String,
+    country: String,
+    id: String,
+    checksum: Option<char>,
+}
+
+impl Uvci {
+    /// Version of the UVCI schema, e.g. `01`
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Country of the issuer
+    pub fn country(&self) -> &str {
+        &self.country
+    }
+
+    /// Opaque identifier, as defined by the issuer
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn checksum(&self) -> Option<char> {
+        self.checksum
+    }
+
+    fn without_checksum(&self) -> String {
+        format!("{}{}:{}:{}", PREFIX, self.version, self.country, self.id)
+    }
+}
+
+impl FromStr for Uvci {
+    type Err = UvciError;
+
+    /// The `URN:UVCI:` prefix is optional and case insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use UvciError::*;
+
+        let s = s.trim();
+
+        let s = match s.get(..PREFIX.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(PREFIX) => &s[PREFIX.len()..],
+            _ => s,
+        };
+
+        let (s, checksum) = match s.split_once('#') {
+            Some((s, checksum)) => {
+                let mut chars = checksum.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => (s, Some(c)),
+                    _ => return Err(InvalidCharacter('#')),
+                }
+            }
+            None => (s, None),
+        };
+
+        let mut parts = s.splitn(3, ':');
+
+        let version = parts.next().unwrap_or_default();
+
+        if version.len() != 2 || !version.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(InvalidVersion(version.to_owned()));
+        }
+
+        let country = parts.next().unwrap_or_default();
+
+        if country.len() != 2 || !country.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(InvalidCountry(country.to_owned()));
+        }
+
+        let id = parts.next().unwrap_or_default();
+
+        if id.is_empty() {
+            return Err(EmptyIdentifier);
+        }
+
+        if let Some(invalid) = id
+            .chars()
+            .find(|c| !c.is_ascii() || !ALPHABET.contains(&(*c as u8)))
+        {
+            return Err(InvalidCharacter(invalid));
+        }
+
+        let uvci = Uvci {
+            version: version.to_owned(),
+            country: country.to_owned(),
+            id: id.to_owned(),
+            checksum,
+        };
+
+        if let Some(found) = checksum {
+            let with_prefix = uvci.without_checksum();
+
+            // Issuers disagree on whether the prefix is part of the checksum.
+            let expected = luhn_mod_n(&with_prefix);
+
+            if found != expected && found != luhn_mod_n(&with_prefix[PREFIX.len()..]) {
+                return Err(InvalidChecksum { expected, found });
+            }
+        }
+
+        Ok(uvci)
+    }
+}
+
+/// Normalized form, always prefixed with `URN:UVCI:`.
+impl fmt::Display for Uvci {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.without_checksum())?;
+
+        match self.checksum {
+            Some(checksum) => write!(f, "#{}", checksum),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Luhn mod N check character of `input`, whose characters must all be in
+/// [`ALPHABET`].
+fn luhn_mod_n(input: &str) -> char {
+    check_character(ALPHABET, input)
+}
+
+fn check_character(alphabet: &[u8], input: &str) -> char {
+    let n = alphabet.len();
+
+    let mut factor = 2;
+    let mut sum = 0;
+
+    for c in input.bytes().rev() {
+        let code_point = alphabet.iter().position(|a| *a == c).unwrap_or(0);
+
+        let addend = factor * code_point;
+
+        factor = if factor == 2 { 1 } else { 2 };
+
+        sum += addend / n + addend % n;
+    }
+
+    let check = (n - sum % n) % n;
+
+    alphabet[check] as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use UvciError::*;
+
+    #[test]
+    fn luhn_mod_n_reference_examples() {
+        // Luhn mod 10 is the original Luhn algorithm.
+        assert_eq!(check_character(b"0123456789", "7992739871"), '3');
+
+        // Example given with the definition of Luhn mod N.
+        assert_eq!(check_character(b"abcdef", "abcdef"), 'e');
+    }
+
+    #[test]
+    fn parses_identifiers() {
+        let uvci: Uvci = "URN:UVCI:01:NL:187/37512422923".parse().unwrap();
+
+        assert_eq!(uvci.version(), "01");
+        assert_eq!(uvci.country(), "NL");
+        assert_eq!(uvci.id(), "187/37512422923");
+        assert_eq!(uvci.checksum(), None);
+        assert_eq!(uvci.to_string(), "URN:UVCI:01:NL:187/37512422923");
+    }
+
+    #[test]
+    fn prefix_is_optional_and_case_insensitive() {
+        for ci in [
+            "01:NL:187/37512422923",
+            "urn:uvci:01:NL:187/37512422923",
+            "Urn:Uvci:01:NL:187/37512422923",
+            "  URN:UVCI:01:NL:187/37512422923  ",
+        ] {
+            let uvci: Uvci = ci.parse().unwrap();
+
+            assert_eq!(uvci.to_string(), "URN:UVCI:01:NL:187/37512422923", "{}", ci);
+        }
+    }
+
+    #[test]
+    fn only_the_prefix_is_case_folded() {
+        assert_eq!(
+            "URN:UVCI:01:nl:187/37512422923".parse::<Uvci>(),
+            Err(InvalidCountry("nl".into()))
+        );
+        assert_eq!(
+            "URN:UVCI:01:NL:187/abc".parse::<Uvci>(),
+            Err(InvalidCharacter('a'))
+        );
+    }
+
+    #[test]
+    fn checksum_may_include_the_prefix() {
+        let with_prefix = luhn_mod_n("URN:UVCI:01:NL:187/37512422923");
+        let without_prefix = luhn_mod_n("01:NL:187/37512422923");
+
+        assert_eq!(with_prefix, 'K');
+        assert_eq!(without_prefix, '1');
+
+        for checksum in [with_prefix, without_prefix] {
+            let ci = format!("URN:UVCI:01:NL:187/37512422923#{}", checksum);
+            let uvci: Uvci = ci.parse().unwrap();
+
+            assert_eq!(uvci.checksum(), Some(checksum));
+            assert_eq!(uvci.to_string(), ci);
+
+            // The checksum does not depend on how the prefix was written.
+            assert!(format!("urn:uvci:01:NL:187/37512422923#{}", checksum)
+                .parse::<Uvci>()
+                .is_ok());
+        }
+
+        assert_eq!(
+            "URN:UVCI:01:NL:187/37512422923#A".parse::<Uvci>(),
+            Err(InvalidChecksum {
+                expected: 'K',
+                found: 'A'
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_identifiers() {
+        for (ci, error) in [
+            ("URN:UVCI:1:NL:187", InvalidVersion("1".into())),
+            ("URN:UVCI:AB:NL:187", InvalidVersion("AB".into())),
+            ("URN:UVCI:01:NLD:187", InvalidCountry("NLD".into())),
+            ("URN:UVCI:01:NL", EmptyIdentifier),
+            ("URN:UVCI:01:NL:", EmptyIdentifier),
+            ("URN:UVCI:01:NL:187_375", InvalidCharacter('_')),
+            ("URN:UVCI:01:NL:187#", InvalidCharacter('#')),
+            ("URN:UVCI:01:NL:187#AB", InvalidCharacter('#')),
+            ("URN:UVCI:01:NL:1Ä7", InvalidCharacter('Ä')),
+        ] {
+            assert_eq!(ci.parse::<Uvci>(), Err(error), "{}", ci);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+
+use super::ValueSetError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceStatus {
+    Approved,
+    NotApproved,
+    UnknownDevice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeviceManufacturer {
+    #[serde(rename = "id_manufacturer", default)]
+    pub id: String,
+
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ListChange {
+    /// Date the list was updated, e.g. `2021-07-09 12:00:00 CET`
+    pub list_date: String,
+
+    pub in_common_list: bool,
+}
+
+impl ListChange {
+    /// Only the day is taken into account, ignoring the time and time zone.
+    fn date(&self) -> Option<NaiveDate> {
+        let date = self.list_date.get(..10)?;
+
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RatDevice {
+    #[serde(rename = "id_device")]
+    pub id: String,
+
+    pub commercial_name: String,
+
+    pub manufacturer: DeviceManufacturer,
+
+    /// Whether the device is currently on the common list
+    pub hsc_common_list: bool,
+
+    #[serde(default)]
+    pub hsc_list_history: Vec<ListChange>,
+}
+
+impl RatDevice {
+    /// Whether the device was on the common list at `at`, according to the
+    /// history of the list, or its current state if it has no history.
+    pub fn approved_at(&self, at: DateTime<Utc>) -> bool {
+        if self.hsc_list_history.is_empty() {
+            return self.hsc_common_list;
+        }
+
+        let at = at.naive_utc().date();
+
+        self.hsc_list_history
+            .iter()
+            .filter_map(|change| change.date().map(|date| (date, change)))
+            .filter(|(date, _)| *date <= at)
+            .max_by_key(|(date, _)| *date)
+            .map(|(_, change)| change.in_common_list)
+            .unwrap_or(false)
+    }
+}
+
+impl fmt::Display for RatDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, {}", self.manufacturer.name, self.commercial_name)
+    }
+}
+
+#[derive(Deserialize)]
+struct DeviceListExport {
+    #[serde(rename = "deviceList")]
+    device_list: Vec<RatDevice>,
+}
+
+/// The EU common list of rapid antigen tests maintained by the JRC, as
+/// exported in JSON from its website.
+#[derive(Debug, Clone, Default)]
+pub struct RatDevices {
+    devices: HashMap<String, RatDevice>,
+}
+
+impl RatDevices {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ValueSetError> {
+        let file = File::open(path).map_err(ValueSetError::FileError)?;
+
+        self.load(BufReader::new(file))
+    }
+
+    pub fn load<R: Read>(&mut self, r: R) -> Result<(), ValueSetError> {
+        let export: DeviceListExport =
+            serde_json::from_reader(r).map_err(ValueSetError::ParsingError)?;
+
+        log::debug!(target:"dgc", "Loaded {} rapid antigen test devices.", export.device_list.len());
+
+        self.devices.extend(
+            export
+                .device_list
+                .into_iter()
+                .map(|device| (device.id.clone(), device)),
+        );
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&RatDevice> {
+        self.devices.get(id)
+    }
+
+    pub fn status_at(&self, id: &str, at: DateTime<Utc>) -> DeviceStatus {
+        match self.get(id) {
+            Some(device) if device.approved_at(at) => DeviceStatus::Approved,
+            Some(_) => DeviceStatus::NotApproved,
+            None => DeviceStatus::UnknownDevice,
+        }
+    }
+}
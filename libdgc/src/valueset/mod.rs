@@ -0,0 +1,212 @@
+//! Codes of the EU DCC value sets, as found in the `tg`, `vp`, `mp`, `ma`,
+//! `tt` and `tr` fields of certificates.
+//!
+//! The enums only know the codes of their time; display names come from a
+//! [`ValueSets`] registry which can be updated at runtime.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+mod devices;
+mod registry;
+
+pub use devices::{DeviceManufacturer, DeviceStatus, ListChange, RatDevice, RatDevices};
+pub use registry::{
+    ValueSet, ValueSetEntry, ValueSetError, ValueSets, DISEASE_AGENT_TARGETED, TEST_DEVICE,
+    TEST_RESULT, TEST_TYPE, VACCINE_MANUFACTURER, VACCINE_MEDICINAL_PRODUCT, VACCINE_PROPHYLAXIS,
+};
+
+macro_rules! value_set {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident => $code:literal, $display:literal;)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+
+            /// A code missing from the value set
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn from_code(code: &str) -> Self {
+                match code {
+                    $($code => $name::$variant,)*
+                    unknown => $name::Unknown(unknown.to_owned()),
+                }
+            }
+
+            pub fn code(&self) -> &str {
+                match self {
+                    $($name::$variant => $code,)*
+                    $name::Unknown(code) => code,
+                }
+            }
+
+            pub fn is_known(&self) -> bool {
+                !matches!(self, $name::Unknown(_))
+            }
+        }
+
+        /// Human readable name, or the code itself if unknown.
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $($name::$variant => f.write_str($display),)*
+                    $name::Unknown(code) => f.write_str(code),
+                }
+            }
+        }
+
+        /// Serialized as its code.
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(self.code())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                String::deserialize(deserializer).map(|code| $name::from_code(&code))
+            }
+        }
+    };
+}
+
+value_set! {
+    /// Disease or agent targeted (`tg`)
+    pub enum Disease {
+        Covid19 => "840539006", "COVID-19";
+    }
+}
+
+value_set! {
+    /// Type of vaccine or prophylaxis (`vp`)
+    pub enum VaccineType {
+        AntigenVaccine => "1119305005", "SARS-CoV2 antigen vaccine";
+        MrnaVaccine => "1119349007", "SARS-CoV2 mRNA vaccine";
+        Covid19Vaccines => "J07BX03", "covid-19 vaccines";
+    }
+}
+
+value_set! {
+    /// Vaccine medicinal product (`mp`)
+    pub enum MedicinalProduct {
+        Comirnaty => "EU/1/20/1528", "Comirnaty";
+        Spikevax => "EU/1/20/1507", "COVID-19 Vaccine Moderna";
+        Vaxzevria => "EU/1/21/1529", "Vaxzevria";
+        Janssen => "EU/1/20/1525", "COVID-19 Vaccine Janssen";
+        Nuvaxovid => "EU/1/21/1618", "Nuvaxovid";
+        CVnCoV => "CVnCoV", "CVnCoV";
+        NvxCov2373 => "NVX-CoV2373", "NVX-CoV2373";
+        SputnikV => "Sputnik-V", "Sputnik-V";
+        Convidecia => "Convidecia", "Convidecia";
+        EpiVacCorona => "EpiVacCorona", "EpiVacCorona";
+        BbibpCorV => "BBIBP-CorV", "BBIBP-CorV";
+        InactivatedVeroCell => "Inactivated-SARS-CoV-2-Vero-Cell", "Inactivated SARS-CoV-2 (Vero Cell)";
+        CoronaVac => "CoronaVac", "CoronaVac";
+        Covaxin => "Covaxin", "Covaxin (also known as BBV152 A, B, C)";
+    }
+}
+
+value_set! {
+    /// Vaccine marketing authorization holder or manufacturer (`ma`)
+    pub enum Manufacturer {
+        AstraZeneca => "ORG-100001699", "AstraZeneca AB";
+        Biontech => "ORG-100030215", "Biontech Manufacturing GmbH";
+        Janssen => "ORG-100001417", "Janssen-Cilag International";
+        Moderna => "ORG-100031184", "Moderna Biotech Spain S.L.";
+        Curevac => "ORG-100006270", "Curevac AG";
+        CanSino => "ORG-100013793", "CanSino Biologics";
+        SinopharmBeijing => "ORG-100020693", "China Sinopharm International Corp. - Beijing location";
+        SinopharmPrague => "ORG-100010771", "Sinopharm Weiqida Europe Pharmaceutical s.r.o. - Prague location";
+        SinopharmShenzhen => "ORG-100024420", "Sinopharm Zhijun (Shenzhen) Pharmaceutical Co. Ltd. - Shenzhen location";
+        Novavax => "ORG-100032020", "Novavax CZ AS";
+        Gamaleya => "Gamaleya-Research-Institute", "Gamaleya Research Institute";
+        Vector => "Vector-Institute", "Vector Institute";
+        Sinovac => "Sinovac-Biotech", "Sinovac Biotech";
+        Bharat => "Bharat-Biotech", "Bharat Biotech";
+    }
+}
+
+value_set! {
+    /// Type of test (`tt`)
+    pub enum TestType {
+        Naat => "LP6464-4", "Nucleic acid amplification with probe detection";
+        RapidAntigen => "LP217198-3", "Rapid immunoassay";
+    }
+}
+
+value_set! {
+    /// Result of the test (`tr`)
+    pub enum TestResult {
+        NotDetected => "260415000", "Not detected";
+        Detected => "260373001", "Detected";
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_known_codes() {
+        for code in ["1119305005", "1119349007", "J07BX03"] {
+            let vaccine_type = VaccineType::from_code(code);
+
+            assert!(vaccine_type.is_known());
+            assert_eq!(vaccine_type.code(), code);
+        }
+
+        assert_eq!(Disease::from_code("840539006"), Disease::Covid19);
+        assert_eq!(
+            MedicinalProduct::from_code("EU/1/20/1528"),
+            MedicinalProduct::Comirnaty
+        );
+        assert_eq!(Manufacturer::Biontech.code(), "ORG-100030215");
+        assert_eq!(TestType::RapidAntigen.code(), "LP217198-3");
+        assert_eq!(TestResult::from_code("260373001"), TestResult::Detected);
+        assert_eq!(TestResult::Detected.to_string(), "Detected");
+    }
+
+    #[test]
+    fn keeps_unknown_codes() {
+        let product = MedicinalProduct::from_code("EU/1/99/0000");
+
+        assert_eq!(
+            product,
+            MedicinalProduct::Unknown("EU/1/99/0000".to_owned())
+        );
+        assert!(!product.is_known());
+        assert_eq!(product.code(), "EU/1/99/0000");
+        assert_eq!(product.to_string(), "EU/1/99/0000");
+    }
+
+    #[test]
+    fn serializes_codes() {
+        let json = serde_json::to_string(&TestResult::NotDetected).unwrap();
+
+        assert_eq!(json, r#""260415000""#);
+        assert_eq!(
+            serde_json::from_str::<TestResult>(&json).unwrap(),
+            TestResult::NotDetected
+        );
+
+        let unknown = TestResult::from_code("1240581000000104");
+        let json = serde_json::to_string(&unknown).unwrap();
+
+        assert_eq!(json, r#""1240581000000104""#);
+        assert_eq!(serde_json::from_str::<TestResult>(&json).unwrap(), unknown);
+    }
+}
@@ -0,0 +1,296 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use super::RatDevices;
+
+pub const DISEASE_AGENT_TARGETED: &str = "disease-agent-targeted";
+pub const VACCINE_PROPHYLAXIS: &str = "sct-vaccines-covid-19";
+pub const VACCINE_MEDICINAL_PRODUCT: &str = "vaccines-covid-19-names";
+pub const VACCINE_MANUFACTURER: &str = "vaccines-covid-19-auth-holders";
+pub const TEST_TYPE: &str = "covid-19-lab-test-type";
+pub const TEST_RESULT: &str = "covid-19-lab-result";
+pub const TEST_DEVICE: &str = "covid-19-lab-test-manufacturer-and-name";
+
+/// Snapshot of the value sets published in the eu-dcc-valuesets repository.
+const BUNDLED: &[&str] = &[
+    include_str!("../../valuesets/disease-agent-targeted.json"),
+    include_str!("../../valuesets/sct-vaccines-covid-19.json"),
+    include_str!("../../valuesets/vaccines-covid-19-names.json"),
+    include_str!("../../valuesets/vaccines-covid-19-auth-holders.json"),
+    include_str!("../../valuesets/covid-19-lab-test-type.json"),
+    include_str!("../../valuesets/covid-19-lab-result.json"),
+];
+
+static GLOBAL: Lazy<RwLock<Arc<ValueSets>>> =
+    Lazy::new(|| RwLock::new(Arc::new(ValueSets::bundled())));
+
+#[derive(Debug)]
+pub enum ValueSetError {
+    FileError(std::io::Error),
+    ParsingError(serde_json::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ValueSetEntry {
+    pub display: String,
+
+    #[serde(default)]
+    pub lang: String,
+
+    /// Inactive codes are kept so that older certificates still display,
+    /// but should not be accepted anymore.
+    #[serde(default = "active_by_default")]
+    pub active: bool,
+
+    #[serde(default)]
+    pub system: String,
+
+    #[serde(default)]
+    pub version: String,
+}
+
+fn active_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ValueSet {
+    #[serde(rename = "valueSetId")]
+    pub id: String,
+
+    /// Version of the value set
+    #[serde(rename = "valueSetDate")]
+    pub date: NaiveDate,
+
+    #[serde(rename = "valueSetValues")]
+    pub values: HashMap<String, ValueSetEntry>,
+}
+
+impl ValueSet {
+    pub fn get(&self, code: &str) -> Option<&ValueSetEntry> {
+        self.values.get(code)
+    }
+}
+
+/// Value sets by id, used to display and check the codes found in
+/// certificates.
+#[derive(Debug, Clone, Default)]
+pub struct ValueSets {
+    sets: HashMap<String, ValueSet>,
+    rat_devices: RatDevices,
+}
+
+impl ValueSets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The value sets shipped with this library, which may be outdated.
+    pub fn bundled() -> Self {
+        let mut value_sets = Self::new();
+
+        for json in BUNDLED {
+            value_sets
+                .load(json.as_bytes())
+                .expect("Bundled value sets are valid.");
+        }
+
+        value_sets
+    }
+
+    /// Loads every JSON file found in `dir`, such as a checkout of the
+    /// eu-dcc-valuesets repository.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<(), ValueSetError> {
+        use ValueSetError::FileError;
+
+        for entry in fs::read_dir(dir).map_err(FileError)? {
+            let path = entry.map_err(FileError)?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+                self.load_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ValueSetError> {
+        let file = File::open(path).map_err(ValueSetError::FileError)?;
+
+        self.load(BufReader::new(file))
+    }
+
+    /// Adds a value set, replacing any older version of it.
+    pub fn load<R: Read>(&mut self, r: R) -> Result<(), ValueSetError> {
+        let value_set: ValueSet =
+            serde_json::from_reader(r).map_err(ValueSetError::ParsingError)?;
+
+        log::debug!(target:"dgc", "Loaded value set '{}' from {}: {} values", value_set.id, value_set.date, value_set.values.len());
+
+        match self.sets.get(&value_set.id) {
+            Some(current) if current.date > value_set.date => {
+                log::warn!(target:"dgc", "Ignoring value set '{}' from {}, older than the one loaded.", value_set.id, value_set.date);
+            }
+            _ => {
+                self.sets.insert(value_set.id.clone(), value_set);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ValueSet> {
+        self.sets.get(id)
+    }
+
+    pub fn sets(&self) -> impl Iterator<Item = &ValueSet> {
+        self.sets.values()
+    }
+
+    pub fn display(&self, id: &str, code: &str) -> Option<&str> {
+        self.entry(id, code).map(|entry| entry.display.as_str())
+    }
+
+    /// Whether `code` is part of the value set and still active.
+    pub fn is_valid(&self, id: &str, code: &str) -> bool {
+        matches!(self.entry(id, code), Some(entry) if entry.active)
+    }
+
+    /// The JRC list of rapid antigen tests, more detailed than the
+    /// `covid-19-lab-test-manufacturer-and-name` value set.
+    pub fn rat_devices(&self) -> &RatDevices {
+        &self.rat_devices
+    }
+
+    pub fn set_rat_devices(&mut self, rat_devices: RatDevices) {
+        self.rat_devices = rat_devices;
+    }
+
+    fn entry(&self, id: &str, code: &str) -> Option<&ValueSetEntry> {
+        self.get(id).and_then(|value_set| value_set.get(code))
+    }
+
+    /// Value sets used when displaying certificates, the bundled ones unless
+    /// replaced with [`ValueSets::install`].
+    pub fn global() -> Arc<ValueSets> {
+        GLOBAL.read().unwrap().clone()
+    }
+
+    /// Replaces the global value sets, which every certificate displayed
+    /// afterwards uses, in any thread. Handles already returned by
+    /// [`ValueSets::global`] keep the previous ones; pass a [`ValueSets`]
+    /// explicitly where certificates must not depend on global state, as
+    /// to [`ExternalParameters`](crate::certlogic::ExternalParameters).
+    pub fn install(self) {
+        *GLOBAL.write().unwrap() = Arc::new(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lab_result(date: &str, detected: &str, active: bool) -> String {
+        format!(
+            r#"{{
+                "valueSetId": "{}",
+                "valueSetDate": "{}",
+                "valueSetValues": {{
+                    "260373001": {{ "display": "{}", "active": {} }}
+                }}
+            }}"#,
+            TEST_RESULT, date, detected, active
+        )
+    }
+
+    #[test]
+    fn loads_directories() {
+        let mut value_sets = ValueSets::new();
+        value_sets
+            .load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/valuesets"))
+            .unwrap();
+
+        let bundled = ValueSets::bundled();
+
+        assert_eq!(value_sets.sets().count(), BUNDLED.len());
+
+        for value_set in bundled.sets() {
+            assert_eq!(value_sets.get(&value_set.id), Some(value_set));
+        }
+
+        assert!(ValueSets::new().load_dir("/nonexistent").is_err());
+    }
+
+    #[test]
+    fn keeps_the_newest_version() {
+        let mut value_sets = ValueSets::bundled();
+
+        assert_eq!(
+            value_sets.display(TEST_RESULT, "260373001"),
+            Some("Detected")
+        );
+
+        value_sets
+            .load(lab_result("2022-01-01", "Newer", true).as_bytes())
+            .unwrap();
+
+        assert_eq!(value_sets.display(TEST_RESULT, "260373001"), Some("Newer"));
+
+        // Code missing from the newer version
+        assert_eq!(value_sets.display(TEST_RESULT, "260415000"), None);
+
+        value_sets
+            .load(lab_result("2021-01-01", "Older", true).as_bytes())
+            .unwrap();
+
+        assert_eq!(value_sets.display(TEST_RESULT, "260373001"), Some("Newer"));
+    }
+
+    #[test]
+    fn only_accepts_active_codes() {
+        let mut value_sets = ValueSets::bundled();
+
+        assert!(value_sets.is_valid(TEST_RESULT, "260373001"));
+        assert!(!value_sets.is_valid(TEST_RESULT, "000000000"));
+        assert!(!value_sets.is_valid("unknown-value-set", "260373001"));
+
+        value_sets
+            .load(lab_result("2022-01-01", "Detected", false).as_bytes())
+            .unwrap();
+
+        // Inactive codes are still displayed.
+        assert!(!value_sets.is_valid(TEST_RESULT, "260373001"));
+        assert_eq!(
+            value_sets.display(TEST_RESULT, "260373001"),
+            Some("Detected")
+        );
+    }
+
+    #[test]
+    fn installs_global_value_sets() {
+        let previous = ValueSets::global();
+
+        let mut value_sets = ValueSets::bundled();
+        value_sets
+            .load(lab_result("2099-01-01", "Installed", true).as_bytes())
+            .unwrap();
+        value_sets.install();
+
+        assert_eq!(
+            ValueSets::global().display(TEST_RESULT, "260373001"),
+            Some("Installed")
+        );
+        assert_eq!(previous.display(TEST_RESULT, "260373001"), Some("Detected"));
+
+        ValueSets::bundled().install();
+    }
+}
@@ -0,0 +1,248 @@
+//! Runs the CertLogic test suites found in `tests/certlogic`:
+//!
+//! - `testSuite`, the official suite of the reference implementation, whose
+//!   files are vendored unchanged,
+//! - `cases`, cases written for this crate in the same format.
+
+use std::{fs, path::Path};
+
+use libdgc::certlogic::{evaluate, CertLogicError};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Assertions of the official suite this implementation does not pass, as
+/// `<suite> / <case> #<assertion index>`.
+const KNOWN_FAILURES: &[&str] = &[];
+
+#[derive(Deserialize)]
+struct TestSuite {
+    name: String,
+    directive: Option<Directive>,
+    cases: Vec<TestCase>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestCase {
+    name: String,
+    directive: Option<Directive>,
+    cert_logic_expression: Value,
+    assertions: Vec<Assertion>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Assertion {
+    directive: Option<Directive>,
+    /// Replaces the expression of the case
+    cert_logic_expression: Option<Value>,
+    data: Value,
+    expected: Value,
+}
+
+/// Only `skip` is honored: `only` just helps debugging the reference
+/// implementation.
+#[derive(Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Directive {
+    Skip,
+    Only,
+}
+
+fn skipped(directive: &Option<Directive>) -> bool {
+    *directive == Some(Directive::Skip)
+}
+
+/// Runs every suite of `dir`, returning the number of assertions run and the
+/// failed ones.
+fn run_suites(dir: &str) -> (usize, Vec<(String, String)>) {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(dir);
+
+    let mut failures = vec![];
+    let mut count = 0;
+
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let suite: TestSuite = serde_json::from_slice(&fs::read(&path).unwrap())
+            .unwrap_or_else(|e| panic!("Invalid test suite {}: {}", path.display(), e));
+
+        if skipped(&suite.directive) {
+            continue;
+        }
+
+        for case in suite.cases.iter().filter(|case| !skipped(&case.directive)) {
+            for (i, assertion) in case.assertions.iter().enumerate() {
+                if skipped(&assertion.directive) {
+                    continue;
+                }
+
+                let expr = assertion
+                    .cert_logic_expression
+                    .as_ref()
+                    .unwrap_or(&case.cert_logic_expression);
+
+                let result = evaluate(expr, &assertion.data).map(|value| value.to_json());
+
+                count += 1;
+
+                if result.as_ref().ok() != Some(&assertion.expected) {
+                    failures.push((
+                        format!("{} / {} #{}", suite.name, case.name, i),
+                        format!("expected {}, got {:?}", assertion.expected, result),
+                    ));
+                }
+            }
+        }
+    }
+
+    (count, failures)
+}
+
+#[test]
+fn official_test_suite() {
+    let (count, failures) = run_suites("tests/certlogic/testSuite");
+
+    if count == 0 {
+        eprintln!(
+            "The official CertLogic test suite is not vendored in tests/certlogic/testSuite."
+        );
+        return;
+    }
+
+    let unexpected: Vec<_> = failures
+        .iter()
+        .filter(|(id, _)| !KNOWN_FAILURES.contains(&id.as_str()))
+        .map(|(id, failure)| format!("{}: {}", id, failure))
+        .collect();
+
+    let fixed: Vec<_> = KNOWN_FAILURES
+        .iter()
+        .filter(|known| !failures.iter().any(|(id, _)| id == *known))
+        .collect();
+
+    assert!(unexpected.is_empty(), "{}", unexpected.join("\n"));
+    assert!(fixed.is_empty(), "Known failures now passing: {:?}", fixed);
+}
+
+#[test]
+fn own_cases() {
+    let (count, failures) = run_suites("tests/certlogic/cases");
+
+    let failures: Vec<_> = failures
+        .iter()
+        .map(|(id, failure)| format!("{}: {}", id, failure))
+        .collect();
+
+    assert!(count > 0, "No test case found.");
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+fn fails(expr: Value, data: Value) -> CertLogicError {
+    match evaluate(&expr, &data) {
+        Ok(value) => panic!("{} evaluated to {:?}", expr, value),
+        Err(e) => e,
+    }
+}
+
+#[test]
+fn partial_dates_are_only_dates_of_birth() {
+    for date in [
+        "2021",
+        "2021-06",
+        "2021-06-01T10:00",
+        "21-06-01",
+        "2021-06-01 10:00:00Z",
+    ] {
+        assert!(matches!(
+            fails(json!({ "plusTime": [date, 0, "day"] }), json!({})),
+            CertLogicError::InvalidDateTime(_)
+        ));
+    }
+
+    for dob in [
+        "1980-05-01T00:00:00Z",
+        "1980-5",
+        "80",
+        "1980-13",
+        "1980-02-30",
+    ] {
+        assert!(matches!(
+            fails(json!({ "dccDateOfBirth": [dob] }), json!({})),
+            CertLogicError::InvalidDateTime(_)
+        ));
+    }
+}
+
+#[test]
+fn guards_must_be_truthy_or_falsy() {
+    let date_time = json!({ "plusTime": ["2021-06-01", 0, "day"] });
+
+    for guard in [json!({ "var": "empty" }), date_time] {
+        let data = json!({ "empty": {} });
+
+        assert!(matches!(
+            fails(json!({ "if": [guard.clone(), 1, 2] }), data.clone()),
+            CertLogicError::NotBoolean("if")
+        ));
+        assert!(matches!(
+            fails(json!({ "!": [guard.clone()] }), data.clone()),
+            CertLogicError::NotBoolean("!")
+        ));
+        assert!(matches!(
+            fails(json!({ "and": [true, guard] }), data),
+            CertLogicError::NotBoolean("and")
+        ));
+    }
+}
+
+#[test]
+fn comparisons_take_two_or_three_integers() {
+    for operands in [json!([1]), json!([1, 2, 3, 4])] {
+        assert!(matches!(
+            fails(json!({ "<": operands }), json!({})),
+            CertLogicError::InvalidOperands("<")
+        ));
+    }
+
+    assert!(matches!(
+        fails(json!({ "<": [1, "2"] }), json!({})),
+        CertLogicError::TypeMismatch { .. }
+    ));
+    assert!(matches!(
+        fails(json!({ "before": ["2021-06-01", "2021-06-02"] }), json!({})),
+        CertLogicError::TypeMismatch { .. }
+    ));
+}
+
+#[test]
+fn rejects_invalid_expressions() {
+    assert!(matches!(
+        fails(json!({ "unknown": [] }), json!({})),
+        CertLogicError::UnknownOperation(_)
+    ));
+    assert!(matches!(
+        fails(json!(1.5), json!({})),
+        CertLogicError::InvalidExpression(_)
+    ));
+    assert!(matches!(
+        fails(json!({ "if": [true, 1] }), json!({})),
+        CertLogicError::InvalidOperands("if")
+    ));
+    assert!(matches!(
+        fails(json!({ "and": [true] }), json!({})),
+        CertLogicError::InvalidOperands("and")
+    ));
+    assert!(matches!(
+        fails(json!({ "plusTime": ["2021-06-01", 1, "week"] }), json!({})),
+        CertLogicError::InvalidOperands("plusTime")
+    ));
+    assert!(matches!(
+        fails(json!({ "+": [1, "1"] }), json!({})),
+        CertLogicError::TypeMismatch { .. }
+    ));
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use libkeystore::KeyStore;
+
+fn load_keystore() -> (KeyStore, Vec<String>) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../ressources/dcc-certs.json.old");
+
+    let keystore = libkeystore::load_from_file(path).expect("Failed to load keystore.");
+
+    let kids: Vec<String> = keystore.pubkeys().map(|(kid, _)| kid.to_owned()).collect();
+
+    (keystore, kids)
+}
+
+/// Baselines: the certificate is parsed again on every lookup to get its
+/// key, as was done before keys were parsed at load time.
+fn uncached_lookup(c: &mut Criterion) {
+    let (keystore, kids) = load_keystore();
+
+    c.bench_function("verification_key (parse X.509 on lookup)", |b| {
+        b.iter(|| {
+            for kid in &kids {
+                let key = keystore.pubkey_as_cert(kid).map(|cert| {
+                    let spki = cert.public_key();
+
+                    (
+                        spki.algorithm.algorithm.to_id_string(),
+                        spki.subject_public_key.data.to_vec(),
+                    )
+                });
+
+                black_box(key.ok());
+            }
+        })
+    });
+
+    #[allow(deprecated)]
+    c.bench_function("pubkey_for_signature (parse with webpki on lookup)", |b| {
+        b.iter(|| {
+            for kid in &kids {
+                black_box(keystore.pubkey_for_signature(kid).ok());
+            }
+        })
+    });
+}
+
+fn keystore_lookup(c: &mut Criterion) {
+    let (keystore, kids) = load_keystore();
+
+    c.bench_function("verification_key (parsed at load)", |b| {
+        b.iter(|| {
+            for kid in &kids {
+                black_box(keystore.verification_key(kid).ok());
+            }
+        })
+    });
+
+    c.bench_function("pubkeys (cached listing)", |b| {
+        b.iter(|| black_box(keystore.pubkeys().count()))
+    });
+}
+
+criterion_group!(benches, uncached_lookup, keystore_lookup);
+criterion_main!(benches);
@@ -4,12 +4,81 @@ use webpki::EndEntityCert;
 use x509_parser::prelude::*;
 
 use crate::error::{KeystoreError, X509ParsingError};
+use crate::kid::kid_for_certificate;
+use crate::pubkey::VerificationKey;
 
 type KeyContent = [String; 1];
 type KeyStoreInner = HashMap<String, KeyContent>;
 
+/// Certificate details kept around for listings.
+#[derive(Debug, Clone)]
+pub struct KeyInfo {
+    pub issuer: String,
+    pub subject: String,
+    pub not_before: ASN1Time,
+    pub not_after: ASN1Time,
+}
+
+/// Everything extracted from a certificate when it is loaded.
+struct ParsedKey {
+    info: KeyInfo,
+    verification_key: VerificationKey,
+}
+
+struct KeyEntry {
+    /// DER encoded signer certificate
+    der: Vec<u8>,
+
+    /// Key id computed from the certificate itself
+    computed_kid: String,
+
+    /// Parsed certificate, or why it could not be parsed
+    parsed: Result<ParsedKey, String>,
+}
+
+impl KeyEntry {
+    fn new(der: Vec<u8>) -> Self {
+        let computed_kid = kid_for_certificate(&der);
+
+        let parsed = X509Certificate::from_der(&der)
+            .map(|(_, cert)| {
+                let validity = cert.validity();
+
+                ParsedKey {
+                    info: KeyInfo {
+                        issuer: cert.issuer().to_string(),
+                        subject: cert.subject().to_string(),
+                        not_before: validity.not_before,
+                        not_after: validity.not_after,
+                    },
+                    verification_key: VerificationKey::from_spki(cert.public_key()),
+                }
+            })
+            .map_err(|e| e.to_string());
+
+        if let Err(e) = &parsed {
+            log::warn!(target: "keystore", "Unable to parse certificate with computed id '{}': {}", computed_kid, e);
+        }
+
+        KeyEntry {
+            der,
+            computed_kid,
+            parsed,
+        }
+    }
+
+    fn parsed(&self) -> Result<&ParsedKey, KeystoreError> {
+        self.parsed
+            .as_ref()
+            .map_err(|e| X509ParsingError::X509Parser(e.clone()))
+            .map_err(KeystoreError::X509ParsingFailed)
+    }
+}
+
+/// Keys are parsed once when the keystore is built, so that a keystore can be
+/// shared between threads and queried without any further X.509 parsing.
 pub struct KeyStore {
-    inner: HashMap<String, Vec<u8>>,
+    inner: HashMap<String, KeyEntry>,
 }
 
 impl KeyStore {
@@ -18,11 +87,23 @@ impl KeyStore {
 
         for (id, pubkey) in raw_inner {
             if let Some(content) = pubkey.get(0) {
-                if let Ok(decoded) = base64::decode(content) {
-                    log::trace!(target: "keystore", "Added public key with id: {}", id);
+                let der = match base64::decode(content) {
+                    Ok(der) => der,
+                    Err(e) => {
+                        log::warn!(target: "keystore", "Skipping key '{}', invalid base64 certificate: {}", id, e);
+                        continue;
+                    }
+                };
+
+                let entry = KeyEntry::new(der);
 
-                    inner.insert(id, decoded);
+                if entry.computed_kid != id {
+                    log::warn!(target: "keystore", "Key id '{}' does not match its certificate (computed: '{}').", id, entry.computed_kid);
                 }
+
+                log::trace!(target: "keystore", "Added public key with id: {}", id);
+
+                inner.insert(id, entry);
             }
         }
 
@@ -31,12 +112,50 @@ impl KeyStore {
         Self { inner }
     }
 
+    /// Builds a keystore from DER encoded certificates, indexing each one by
+    /// the key id computed from its content.
+    pub fn from_certificates<I>(certificates: I) -> Self
+    where
+        I: IntoIterator<Item = Vec<u8>>,
+    {
+        let inner: HashMap<_, _> = certificates
+            .into_iter()
+            .map(|der| {
+                let entry = KeyEntry::new(der);
+
+                log::trace!(target: "keystore", "Added public key with id: {}", entry.computed_kid);
+
+                (entry.computed_kid.clone(), entry)
+            })
+            .collect();
+
+        log::debug!(target: "keystore", "Added {} keys to keystore.", inner.len());
+
+        Self { inner }
+    }
+
+    /// Key material parsed when the keystore was loaded.
+    pub fn verification_key(&self, kid: &str) -> Result<&VerificationKey, KeystoreError> {
+        let key_entry = self.inner.get(kid).ok_or(KeystoreError::KeyNotFound)?;
+
+        key_entry.parsed().map(|parsed| &parsed.verification_key)
+    }
+
+    pub fn key_info(&self, kid: &str) -> Result<&KeyInfo, KeystoreError> {
+        let key_entry = self.inner.get(kid).ok_or(KeystoreError::KeyNotFound)?;
+
+        key_entry.parsed().map(|parsed| &parsed.info)
+    }
+
+    /// Parses the certificate again on every call, see
+    /// [`KeyStore::verification_key`] for the key parsed at load time.
+    #[deprecated(note = "use `verification_key`, parsed when the keystore is loaded")]
     pub fn pubkey_for_signature(&self, kid: &str) -> Result<EndEntityCert, KeystoreError> {
         use KeystoreError::{KeyNotFound, X509ParsingFailed};
 
         let key_entry = self.inner.get(kid).ok_or(KeyNotFound)?;
 
-        EndEntityCert::try_from(key_entry.as_slice())
+        EndEntityCert::try_from(key_entry.der.as_slice())
             .map_err(X509ParsingError::WebPki)
             .map_err(X509ParsingFailed)
     }
@@ -46,18 +165,150 @@ impl KeyStore {
 
         let key_entry = self.inner.get(kid).ok_or(KeyNotFound)?;
 
-        X509Certificate::from_der(key_entry.as_slice())
+        X509Certificate::from_der(key_entry.der.as_slice())
             .map(|(_, cert)| cert)
             .map_err(|e| X509ParsingError::X509Parser(e.to_string()))
             .map_err(X509ParsingFailed)
     }
 
-    pub fn pubkeys(&self) -> impl Iterator<Item = (&str, X509Certificate)> {
+    /// Lists every key whose certificate could be parsed.
+    pub fn pubkeys(&self) -> impl Iterator<Item = (&str, &KeyInfo)> {
         self.inner.iter().filter_map(|(k, v)| {
-            X509Certificate::from_der(v)
-                .map(|(_, cert)| cert)
+            v.parsed
+                .as_ref()
                 .ok()
-                .map(|c| (k.as_str(), c))
+                .map(|parsed| (k.as_str(), &parsed.info))
         })
     }
+
+    /// Key id computed from the certificate stored under `kid`.
+    pub fn computed_kid(&self, kid: &str) -> Option<&str> {
+        self.inner.get(kid).map(|entry| entry.computed_kid.as_str())
+    }
+
+    /// Entries whose declared key id does not match their certificate, as
+    /// `(declared, computed)` pairs.
+    pub fn mismatched_kids(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.inner
+            .iter()
+            .filter(|(declared, entry)| **declared != entry.computed_kid)
+            .map(|(declared, entry)| (declared.as_str(), entry.computed_kid.as_str()))
+    }
+}
+
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<KeyStore>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyAlgorithm;
+
+    const KEYSTORE: &str = include_str!("../../ressources/dcc-certs.json.old");
+
+    const KID: &str = "fGLuvg6n5wk=";
+
+    fn certificates() -> KeyStoreInner {
+        serde_json::from_str(KEYSTORE).unwrap()
+    }
+
+    #[test]
+    fn computes_kids_of_plain_certificate_lists() {
+        let list: Vec<String> = certificates()
+            .into_values()
+            .map(|[certificate]| certificate)
+            .collect();
+
+        let json = serde_json::to_string(&list).unwrap();
+        let keystore = crate::from_reader(json.as_bytes()).unwrap();
+
+        assert_eq!(keystore.pubkeys().count(), list.len());
+        assert_eq!(keystore.computed_kid(KID), Some(KID));
+        assert_eq!(keystore.mismatched_kids().count(), 0);
+        assert_eq!(
+            keystore.key_info(KID).unwrap().subject,
+            "C=FR, O=CNAM, OU=180035024, CN=DSC_FR_019"
+        );
+    }
+
+    /// Self-signed P-256 certificate, and an ECDSA signature of
+    /// `keystore` made with its key
+    const SIGNER: &str = "MIIBQTCB56ADAgECAgEBMAoGCCqGSM49BAMCMCAxCzAJBgNVBAYTAkRFMREwDwYDVQQDDAhEU0MgdGVzdDAeFw0yMTA2MDEwMDAwMDBaFw0yMzA2MDEwMDAwMDBaMCAxCzAJBgNVBAYTAkRFMREwDwYDVQQDDAhEU0MgdGVzdDBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABNP/FdSklCzkPlcqC5JB0zYmRDW06V2aCLO2fRwJ7OA3N+K8wL6lhYoPdPxyuplEjpcPXFbfMSmUxK4hqbplfqKjEjAQMA4GA1UdDwEB/wQEAwIHgDAKBggqhkjOPQQDAgNJADBGAiEAqWIRLCZ2FuJ+tUaBTAFsbVytYu3qhPc4+NehSCP7bwkCIQDpoxhMDIozQ/6bMQRQxRXRvFFm3PYpW7cPDOWLIdk1tg==";
+    const SIGNATURE: &str = "MEUCIGQQsvYb8ALrKUqPQBoUU/uW3zWSlvXrYjPgwt64ydbWAiEAkFXh35qE0bnd0isuYuRvuMOWW7wwxDWse8v/XdpXmX4=";
+
+    #[test]
+    #[allow(deprecated)]
+    fn cached_keys_match_webpki() {
+        use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1};
+
+        let der = base64::decode(SIGNER).unwrap();
+        let signature = base64::decode(SIGNATURE).unwrap();
+
+        let keystore = KeyStore::from_certificates([der.clone()]);
+        let kid = kid_for_certificate(&der);
+
+        let cached = keystore.verification_key(&kid).unwrap();
+        let reparsed = keystore.pubkey_for_signature(&kid).unwrap();
+
+        assert_eq!(cached.algorithm, KeyAlgorithm::EcdsaP256);
+
+        // Both keys accept the same signature, with the same algorithm.
+        assert!(
+            UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, &cached.public_key)
+                .verify(b"keystore", &signature)
+                .is_ok()
+        );
+        assert!(reparsed
+            .verify_signature(&webpki::ECDSA_P256_SHA256, b"keystore", &signature)
+            .is_ok());
+
+        assert!(reparsed
+            .verify_signature(&webpki::ECDSA_P384_SHA384, b"keystore", &signature)
+            .is_err());
+        assert!(reparsed
+            .verify_signature(&webpki::ECDSA_P256_SHA256, b"other", &signature)
+            .is_err());
+
+        // Published certificates are parsed the same at load time as on
+        // lookup, and webpki accepts them all.
+        let keystore = KeyStore::new(certificates());
+
+        for (kid, _) in keystore.pubkeys() {
+            let cached = keystore.verification_key(kid).unwrap();
+            let reparsed = keystore.pubkey_as_cert(kid).unwrap();
+
+            assert_eq!(cached.algorithm, KeyAlgorithm::EcdsaP256);
+            assert_eq!(
+                cached.public_key,
+                reparsed.public_key().subject_public_key.data
+            );
+            assert!(keystore.pubkey_for_signature(kid).is_ok());
+        }
+    }
+
+    #[test]
+    fn reports_mismatched_kids() {
+        let mut inner = certificates();
+
+        let certificate = inner.remove(KID).unwrap();
+        inner.insert("AAAAAAAAAAA=".into(), certificate);
+
+        let keystore = KeyStore::new(inner);
+
+        assert_eq!(
+            keystore.mismatched_kids().collect::<Vec<_>>(),
+            vec![("AAAAAAAAAAA=", KID)]
+        );
+        assert_eq!(keystore.computed_kid("AAAAAAAAAAA="), Some(KID));
+
+        // The entry is still usable under its declared id.
+        assert!(keystore.verification_key("AAAAAAAAAAA=").is_ok());
+        assert!(matches!(
+            keystore.verification_key(KID),
+            Err(KeystoreError::KeyNotFound)
+        ));
+    }
 }
@@ -0,0 +1,34 @@
+use sha2::{Digest, Sha256};
+
+/// Length, in bytes, of a DCC key identifier.
+const KID_LEN: usize = 8;
+
+/// Computes the key identifier of a signer certificate: the first 8 bytes of
+/// the SHA-256 digest of its DER encoding, base64 encoded.
+pub fn kid_for_certificate(der: &[u8]) -> String {
+    let digest = Sha256::digest(der);
+
+    base64::encode(&digest[..KID_LEN])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYSTORE: &str = include_str!("../../ressources/dcc-certs.json.old");
+
+    #[test]
+    fn truncates_the_sha256_of_the_der() {
+        // SHA-256 of nothing starts with e3b0c44298fc1c14.
+        assert_eq!(kid_for_certificate(b""), "47DEQpj8HBQ=");
+    }
+
+    #[test]
+    fn matches_the_kids_of_published_certificates() {
+        let keystore: serde_json::Value = serde_json::from_str(KEYSTORE).unwrap();
+
+        let der = base64::decode(keystore["fGLuvg6n5wk="][0].as_str().unwrap()).unwrap();
+
+        assert_eq!(kid_for_certificate(&der), "fGLuvg6n5wk=");
+    }
+}
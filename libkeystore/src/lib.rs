@@ -6,11 +6,17 @@ use std::{
 
 use reqwest::IntoUrl;
 
+use serde_json::Value;
+
 mod error;
 mod keystore;
+mod kid;
+mod pubkey;
 
 pub use error::KeystoreError;
-pub use keystore::KeyStore;
+pub use keystore::{KeyInfo, KeyStore};
+pub use kid::kid_for_certificate;
+pub use pubkey::{KeyAlgorithm, PublicKey, VerificationKey};
 
 pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<KeyStore, KeystoreError> {
     use KeystoreError::FileError;
@@ -33,10 +39,32 @@ pub fn load_from_url<U: IntoUrl>(url: U) -> Result<KeyStore, KeystoreError> {
     from_reader(response)
 }
 
+/// Reads a keystore either in the `{ "<kid>": ["<certificate>"] }` format or
+/// as a plain list of base64 encoded certificates, whose key ids are then
+/// computed from the certificates themselves.
 pub fn from_reader<R: Read>(r: R) -> Result<KeyStore, KeystoreError> {
     use KeystoreError::ParsingError;
 
-    serde_json::from_reader(r)
-        .map(KeyStore::new)
-        .map_err(ParsingError)
+    let json: Value = serde_json::from_reader(r).map_err(ParsingError)?;
+
+    if json.is_array() {
+        let certificates: Vec<String> = serde_json::from_value(json).map_err(ParsingError)?;
+
+        let decoded = certificates
+            .iter()
+            .enumerate()
+            .filter_map(|(index, content)| match base64::decode(content) {
+                Ok(der) => Some(der),
+                Err(e) => {
+                    log::warn!(target: "keystore", "Skipping certificate #{}, invalid base64: {}", index, e);
+                    None
+                }
+            });
+
+        Ok(KeyStore::from_certificates(decoded))
+    } else {
+        serde_json::from_value(json)
+            .map(KeyStore::new)
+            .map_err(ParsingError)
+    }
 }
@@ -0,0 +1,205 @@
+use std::{fs, path::Path};
+
+use x509_parser::{pem::parse_x509_pem, prelude::*};
+
+use crate::error::{KeystoreError, X509ParsingError};
+use crate::kid::kid_for_certificate;
+
+const PEM_MARKER: &[u8] = b"-----BEGIN";
+
+/// A single public key, provided outside of any keystore.
+pub enum PublicKey {
+    /// DER encoded signer certificate
+    Certificate(Vec<u8>),
+
+    /// DER encoded SubjectPublicKeyInfo
+    SubjectPublicKeyInfo(Vec<u8>),
+}
+
+impl PublicKey {
+    /// Reads a PEM or DER encoded certificate or SubjectPublicKeyInfo.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, KeystoreError> {
+        log::debug!(target:"keystore", "Loading public key from file: {}", path.as_ref().display());
+
+        let content = fs::read(path).map_err(KeystoreError::FileError)?;
+
+        if content.starts_with(PEM_MARKER) {
+            Self::from_pem(&content)
+        } else {
+            Self::from_der(content)
+        }
+    }
+
+    pub fn from_pem(pem: &[u8]) -> Result<Self, KeystoreError> {
+        let (_, pem) =
+            parse_x509_pem(pem).map_err(|e| X509ParsingError::X509Parser(e.to_string()))?;
+
+        log::trace!(target:"keystore", "Found PEM block: {}", pem.label);
+
+        Self::from_der(pem.contents)
+    }
+
+    /// Tries to read `der` as a certificate first, then as a
+    /// SubjectPublicKeyInfo.
+    pub fn from_der(der: Vec<u8>) -> Result<Self, KeystoreError> {
+        if X509Certificate::from_der(&der).is_ok() {
+            return Ok(PublicKey::Certificate(der));
+        }
+
+        SubjectPublicKeyInfo::from_der(&der)
+            .map_err(|e| X509ParsingError::X509Parser(e.to_string()))?;
+
+        Ok(PublicKey::SubjectPublicKeyInfo(der))
+    }
+
+    /// Key id of the certificate, if this key is one.
+    pub fn kid(&self) -> Option<String> {
+        match self {
+            PublicKey::Certificate(der) => Some(kid_for_certificate(der)),
+            PublicKey::SubjectPublicKeyInfo(_) => None,
+        }
+    }
+
+    /// Extracts the key material, as expected by signature verification
+    /// primitives.
+    pub fn verification_key(&self) -> Result<VerificationKey, KeystoreError> {
+        let parsed = match self {
+            PublicKey::Certificate(der) => X509Certificate::from_der(der)
+                .map(|(_, cert)| VerificationKey::from_spki(cert.public_key())),
+
+            PublicKey::SubjectPublicKeyInfo(der) => SubjectPublicKeyInfo::from_der(der)
+                .map(|(_, spki)| VerificationKey::from_spki(&spki)),
+        };
+
+        parsed
+            .map_err(|e| X509ParsingError::X509Parser(e.to_string()))
+            .map_err(KeystoreError::X509ParsingFailed)
+    }
+}
+
+const OID_EC_PUBLIC_KEY: &str = "1.2.840.10045.2.1";
+const OID_RSA_ENCRYPTION: &str = "1.2.840.113549.1.1.1";
+const OID_CURVE_P256: &str = "1.2.840.10045.3.1.7";
+const OID_CURVE_P384: &str = "1.3.132.0.34";
+const OID_CURVE_P521: &str = "1.3.132.0.35";
+
+/// Algorithm of a public key, as declared in its SubjectPublicKeyInfo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAlgorithm {
+    EcdsaP256,
+    EcdsaP384,
+    EcdsaP521,
+    Rsa,
+    Unsupported,
+}
+
+/// A public key extracted once from its certificate, ready to be handed to
+/// signature verification primitives.
+#[derive(Debug, Clone)]
+pub struct VerificationKey {
+    pub algorithm: KeyAlgorithm,
+
+    /// Content of the `subjectPublicKey` bit string
+    pub public_key: Vec<u8>,
+}
+
+impl VerificationKey {
+    pub(crate) fn from_spki(spki: &SubjectPublicKeyInfo) -> Self {
+        let curve = spki
+            .algorithm
+            .parameters
+            .as_ref()
+            .and_then(|params| params.as_oid().ok())
+            .map(|oid| oid.to_id_string());
+
+        let algorithm = match (
+            spki.algorithm.algorithm.to_id_string().as_str(),
+            curve.as_deref(),
+        ) {
+            (OID_EC_PUBLIC_KEY, Some(OID_CURVE_P256)) => KeyAlgorithm::EcdsaP256,
+            (OID_EC_PUBLIC_KEY, Some(OID_CURVE_P384)) => KeyAlgorithm::EcdsaP384,
+            (OID_EC_PUBLIC_KEY, Some(OID_CURVE_P521)) => KeyAlgorithm::EcdsaP521,
+            (OID_RSA_ENCRYPTION, _) => KeyAlgorithm::Rsa,
+            _ => KeyAlgorithm::Unsupported,
+        };
+
+        VerificationKey {
+            algorithm,
+            public_key: spki.subject_public_key.data.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYSTORE: &str = include_str!("../../ressources/dcc-certs.json.old");
+
+    const KID: &str = "fGLuvg6n5wk=";
+
+    /// P-256 SubjectPublicKeyInfo
+    const SPKI: &str = "MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEqQp37Vnkx7ZwDaPKJkUjX5t5MOkchYeTGr8nTUH+HWyoJsj1iXNR7FNFbcezcSE5EVzV+KAHLGEyKRo51+kaRA==";
+
+    fn certificate() -> String {
+        let keystore: serde_json::Value = serde_json::from_str(KEYSTORE).unwrap();
+
+        keystore[KID][0].as_str().unwrap().to_owned()
+    }
+
+    fn pem(label: &str, base64: &str) -> Vec<u8> {
+        let mut pem = format!("-----BEGIN {}-----\n", label);
+
+        for line in base64.as_bytes().chunks(64) {
+            pem.push_str(std::str::from_utf8(line).unwrap());
+            pem.push('\n');
+        }
+
+        pem.push_str(&format!("-----END {}-----\n", label));
+        pem.into_bytes()
+    }
+
+    #[test]
+    fn reads_certificates_as_pem_or_der() {
+        let der = base64::decode(certificate()).unwrap();
+
+        for key in [
+            PublicKey::from_pem(&pem("CERTIFICATE", &certificate())).unwrap(),
+            PublicKey::from_der(der.clone()).unwrap(),
+        ] {
+            assert!(matches!(&key, PublicKey::Certificate(content) if *content == der));
+            assert_eq!(key.kid().as_deref(), Some(KID));
+            assert_eq!(
+                key.verification_key().unwrap().algorithm,
+                KeyAlgorithm::EcdsaP256
+            );
+        }
+    }
+
+    #[test]
+    fn reads_subject_public_key_infos_as_pem_or_der() {
+        let der = base64::decode(SPKI).unwrap();
+
+        for key in [
+            PublicKey::from_pem(&pem("PUBLIC KEY", SPKI)).unwrap(),
+            PublicKey::from_der(der.clone()).unwrap(),
+        ] {
+            assert!(matches!(&key, PublicKey::SubjectPublicKeyInfo(content) if *content == der));
+            assert_eq!(key.kid(), None);
+
+            let verification_key = key.verification_key().unwrap();
+
+            assert_eq!(verification_key.algorithm, KeyAlgorithm::EcdsaP256);
+            // Uncompressed point
+            assert_eq!(verification_key.public_key.len(), 65);
+            assert_eq!(verification_key.public_key[0], 0x04);
+        }
+    }
+
+    #[test]
+    fn rejects_other_content() {
+        assert!(PublicKey::from_der(b"not a key".to_vec()).is_err());
+        assert!(PublicKey::from_pem(b"not a key").is_err());
+        assert!(PublicKey::from_pem(&pem("PUBLIC KEY", "bm90IGEga2V5")).is_err());
+    }
+}